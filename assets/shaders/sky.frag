@@ -0,0 +1,71 @@
+#version 330 core
+
+// Preetham analytic daylight model, see "A Practical Analytic Model for Daylight" (1999)
+uniform vec3 SunDirection;
+uniform float Turbidity;
+uniform float Exposure;
+
+in VS_OUTPUT {
+    vec3 Direction;
+} IN;
+
+out vec4 Color;
+
+const float PI = 3.14159265;
+
+// Perez luminance distribution for a view at zenith angle theta and angle gamma to the sun
+vec3 perez(float cos_theta, float gamma, float cos_gamma, vec3 A, vec3 B, vec3 C, vec3 D, vec3 E)
+{
+    return (1.0 + A * exp(B / cos_theta)) * (1.0 + C * exp(D * gamma) + E * cos_gamma * cos_gamma);
+}
+
+void main()
+{
+    vec3 view = normalize(IN.Direction);
+    vec3 sun = normalize(SunDirection);
+    float T = Turbidity;
+
+    // Model is only defined above the horizon, the ground mirrors the horizon color
+    float cos_theta = max(view.y, 0.001);
+    float theta_sun = acos(clamp(sun.y, 0.001, 1.0));
+    float cos_gamma = clamp(dot(view, sun), -1.0, 1.0);
+    float gamma = acos(cos_gamma);
+
+    vec3 A = vec3(0.1787 * T - 1.4630, -0.0193 * T - 0.2592, -0.0167 * T - 0.2608);
+    vec3 B = vec3(-0.3554 * T + 0.4275, -0.0665 * T + 0.0008, -0.0950 * T + 0.0092);
+    vec3 C = vec3(-0.0227 * T + 5.3251, -0.0004 * T + 0.2125, -0.0079 * T + 0.2102);
+    vec3 D = vec3(0.1206 * T - 2.5771, -0.0641 * T - 0.8989, -0.0441 * T - 1.6537);
+    vec3 E = vec3(-0.0670 * T + 0.3703, -0.0033 * T + 0.0452, -0.0109 * T + 0.0529);
+
+    // Zenith luminance and chromaticity
+    float chi = (4.0 / 9.0 - T / 120.0) * (PI - 2.0 * theta_sun);
+    float zenith_Y = (4.0453 * T - 4.9710) * tan(chi) - 0.2155 * T + 2.4192;
+    vec3 theta = vec3(theta_sun * theta_sun * theta_sun, theta_sun * theta_sun, theta_sun);
+    float zenith_x = dot(vec3(0.00166, -0.00375, 0.00209), theta) * T * T
+        + (dot(vec3(-0.02903, 0.06377, -0.03202), theta) + 0.00394) * T
+        + dot(vec3(0.11693, -0.21196, 0.06052), theta) + 0.17201;
+    float zenith_y = dot(vec3(0.00275, -0.00610, 0.00317), theta) * T * T
+        + (dot(vec3(-0.04214, 0.08970, -0.04153), theta) + 0.00516) * T
+        + dot(vec3(0.15346, -0.26756, 0.06670), theta) + 0.26688;
+    vec3 zenith = vec3(zenith_Y, zenith_x, zenith_y);
+
+    vec3 Yxy = zenith * perez(cos_theta, gamma, cos_gamma, A, B, C, D, E)
+        / perez(1.0, theta_sun, cos(theta_sun), A, B, C, D, E);
+
+    // Yxy to XYZ to linear sRGB
+    vec3 XYZ = vec3(Yxy.y / Yxy.z * Yxy.x, Yxy.x, (1.0 - Yxy.y - Yxy.z) / Yxy.z * Yxy.x);
+    vec3 rgb = mat3(
+        3.2406, -0.9689, 0.0557,
+        -1.5372, 1.8758, -0.2040,
+        -0.4986, 0.0415, 1.0570
+    ) * XYZ;
+
+    // Sun disk, about half a degree wide
+    rgb += vec3(smoothstep(0.99995, 0.99999, cos_gamma)) * 50.0;
+
+    // Darken towards night as the sun sets
+    rgb *= smoothstep(-0.1, 0.05, sun.y);
+
+    vec3 mapped = 1.0 - exp(-max(rgb, vec3(0.0)) * Exposure);
+    Color = vec4(pow(mapped, vec3(1.0 / 2.2)), 1.0);
+}
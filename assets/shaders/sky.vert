@@ -0,0 +1,18 @@
+#version 330 core
+
+// Fullscreen triangle at the far plane, no vertex buffer needed
+uniform mat4 InverseViewProjection;
+uniform vec3 CameraPosition;
+
+out VS_OUTPUT {
+    vec3 Direction;
+} OUT;
+
+void main()
+{
+    vec2 position = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2) * 2.0 - 1.0;
+    gl_Position = vec4(position, 1.0, 1.0);
+
+    vec4 world = InverseViewProjection * vec4(position, 1.0, 1.0);
+    OUT.Direction = world.xyz / world.w - CameraPosition;
+}
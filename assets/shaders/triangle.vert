@@ -3,12 +3,15 @@
 layout (location = 0) in vec3 Position;
 layout (location = 1) in vec4 Color;
 
+uniform mat4 View;
+uniform mat4 Projection;
+
 out VS_OUTPUT {
     vec3 Color;
 } OUT;
 
 void main()
 {
-    gl_Position = vec4(Position, 1.0);
+    gl_Position = Projection * View * vec4(Position, 1.0);
     OUT.Color = Color.xyz;
 }
@@ -2,6 +2,7 @@ extern crate walkdir;
 
 use std::env;
 use std::fs::{self, DirBuilder};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
@@ -19,6 +20,13 @@ fn main() {
         &manifest_dir.join("assets"),
         &executable_path.join("assets"),
     );
+
+    // list of assets compiled into the executable, empty unless embed_assets is enabled
+    embed(
+        &manifest_dir.join("assets"),
+        &out_dir.join("embedded_assets.rs"),
+        env::var("CARGO_FEATURE_EMBED_ASSETS").is_ok(),
+    );
 }
 
 fn locate_target_dir_from_output_dir(mut target_dir_search: &Path) -> Option<&Path> {
@@ -44,6 +52,9 @@ fn copy(from: &Path, to: &Path) {
     for entry in WalkDir::new(from_path.clone()) {
         let entry = entry.unwrap();
 
+        // rerun when any asset changes, directories catch added and removed files
+        println!("cargo:rerun-if-changed={}", entry.path().display());
+
         if let Ok(rel_path) = entry.path().strip_prefix(&from_path) {
             let target_path = to_path.join(rel_path);
 
@@ -57,3 +68,36 @@ fn copy(from: &Path, to: &Path) {
         }
     }
 }
+
+fn embed(from: &Path, to: &Path, enabled: bool) {
+    let mut file = fs::File::create(to).expect("failed to create embedded assets file");
+    writeln!(file, "pub static ASSETS: &[(&str, &[u8])] = &[").unwrap();
+
+    if enabled {
+        for entry in WalkDir::new(from) {
+            let entry = entry.unwrap();
+
+            if entry.file_type().is_dir() {
+                continue;
+            }
+
+            if let Ok(rel_path) = entry.path().strip_prefix(from) {
+                // resource names always use "/" as separator
+                let name: Vec<_> = rel_path
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy())
+                    .collect();
+
+                writeln!(
+                    file,
+                    "    ({:?}, include_bytes!({:?})),",
+                    name.join("/"),
+                    entry.path()
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    writeln!(file, "];").unwrap();
+}
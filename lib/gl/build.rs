@@ -14,7 +14,7 @@ fn main() {
         (4, 5),
         Profile::Core,
         Fallbacks::All,
-        ["GL_NV_command_list"],
+        ["GL_NV_command_list", "GL_ARB_gl_spirv"],
     );
 
     if env::var("CARGO_FEATURE_DEBUG").is_ok() {
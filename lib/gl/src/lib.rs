@@ -2,15 +2,28 @@ mod bindings {
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 }
 
+#[cfg(feature = "debug")]
+mod validating;
+
 use std::ops::Deref;
 use std::rc::Rc;
 
 pub use crate::bindings::Gl as InnerGl;
 pub use crate::bindings::*;
 
+#[cfg(feature = "debug")]
+pub use crate::validating::ValidatingGl;
+
+// With debug feature calls go through ValidatingGl, which checks bound objects before
+// forwarding to the bindings
+#[cfg(feature = "debug")]
+type Context = ValidatingGl;
+#[cfg(not(feature = "debug"))]
+type Context = bindings::Gl;
+
 #[derive(Clone)]
 pub struct Gl {
-    inner: Rc<bindings::Gl>,
+    inner: Rc<Context>,
 }
 
 impl Gl {
@@ -19,15 +32,15 @@ impl Gl {
         F: FnMut(&'static str) -> *const types::GLvoid,
     {
         Gl {
-            inner: Rc::new(bindings::Gl::load_with(loadfn)),
+            inner: Rc::new(Context::load_with(loadfn)),
         }
     }
 }
 
 impl Deref for Gl {
-    type Target = bindings::Gl;
+    type Target = Context;
 
-    fn deref(&self) -> &bindings::Gl {
+    fn deref(&self) -> &Context {
         &self.inner
     }
 }
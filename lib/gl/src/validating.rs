@@ -0,0 +1,337 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::os::raw::c_void;
+
+use crate::bindings;
+use crate::types;
+
+// Objects the application bound through this context, as seen by the wrapper
+#[derive(Default)]
+struct BoundState {
+    program: types::GLuint,
+    vertex_array: types::GLuint,
+    // Buffers by target, except element array buffers which belong to vertex arrays
+    buffers: HashMap<types::GLenum, types::GLuint>,
+    // Buffers by indexed binding point (target and index), e.g. uniform buffer bindings
+    indexed_buffers: HashMap<(types::GLenum, types::GLuint), types::GLuint>,
+    // Element array buffer of each vertex array
+    element_buffers: HashMap<types::GLuint, types::GLuint>,
+}
+
+// Wrapper around generated bindings which tracks bound objects and panics on misuse that
+// drivers often silently accept: drawing without a program or vertex array (or without an
+// indirect buffer for indirect draws) and setting attribute pointers without a vertex array.
+// Calls which are not validated go straight to the bindings through Deref.
+// Texture and framebuffer bindings are not tracked, so draws are not checked against them.
+pub struct ValidatingGl {
+    inner: bindings::Gl,
+    state: RefCell<BoundState>,
+}
+
+#[allow(non_snake_case)]
+impl ValidatingGl {
+    pub fn load_with<F>(loadfn: F) -> ValidatingGl
+    where
+        F: FnMut(&'static str) -> *const types::GLvoid,
+    {
+        ValidatingGl {
+            inner: bindings::Gl::load_with(loadfn),
+            state: RefCell::new(BoundState::default()),
+        }
+    }
+
+    pub unsafe fn UseProgram(&self, program: types::GLuint) {
+        self.state.borrow_mut().program = program;
+        self.inner.UseProgram(program)
+    }
+
+    pub unsafe fn BindVertexArray(&self, array: types::GLuint) {
+        self.state.borrow_mut().vertex_array = array;
+        self.inner.BindVertexArray(array)
+    }
+
+    pub unsafe fn DeleteVertexArrays(&self, n: types::GLsizei, arrays: *const types::GLuint) {
+        {
+            let mut state = self.state.borrow_mut();
+            for &array in std::slice::from_raw_parts(arrays, n.max(0) as usize) {
+                // Deleting the bound vertex array reverts the binding to zero
+                if array == state.vertex_array {
+                    state.vertex_array = 0;
+                }
+                state.element_buffers.remove(&array);
+            }
+        }
+        self.inner.DeleteVertexArrays(n, arrays)
+    }
+
+    pub unsafe fn BindBuffer(&self, target: types::GLenum, buffer: types::GLuint) {
+        {
+            let mut state = self.state.borrow_mut();
+            if target == bindings::ELEMENT_ARRAY_BUFFER {
+                let vertex_array = state.vertex_array;
+                state.element_buffers.insert(vertex_array, buffer);
+            } else {
+                state.buffers.insert(target, buffer);
+            }
+        }
+        self.inner.BindBuffer(target, buffer)
+    }
+
+    pub unsafe fn BindBufferBase(
+        &self,
+        target: types::GLenum,
+        index: types::GLuint,
+        buffer: types::GLuint,
+    ) {
+        self.track_indexed_buffer(target, index, buffer);
+        self.inner.BindBufferBase(target, index, buffer)
+    }
+
+    pub unsafe fn BindBufferRange(
+        &self,
+        target: types::GLenum,
+        index: types::GLuint,
+        buffer: types::GLuint,
+        offset: types::GLintptr,
+        size: types::GLsizeiptr,
+    ) {
+        self.track_indexed_buffer(target, index, buffer);
+        self.inner
+            .BindBufferRange(target, index, buffer, offset, size)
+    }
+
+    pub unsafe fn DeleteBuffers(&self, n: types::GLsizei, buffers: *const types::GLuint) {
+        {
+            let mut state = self.state.borrow_mut();
+            let vertex_array = state.vertex_array;
+            for &buffer in std::slice::from_raw_parts(buffers, n.max(0) as usize) {
+                if buffer == 0 {
+                    continue;
+                }
+                // Deleting a buffer unbinds it from every binding point of the context,
+                // element array buffers only from the bound vertex array
+                for bound in state.buffers.values_mut() {
+                    if *bound == buffer {
+                        *bound = 0;
+                    }
+                }
+                for bound in state.indexed_buffers.values_mut() {
+                    if *bound == buffer {
+                        *bound = 0;
+                    }
+                }
+                if state.element_buffers.get(&vertex_array) == Some(&buffer) {
+                    state.element_buffers.insert(vertex_array, 0);
+                }
+            }
+        }
+        self.inner.DeleteBuffers(n, buffers)
+    }
+
+    pub unsafe fn VertexAttribPointer(
+        &self,
+        index: types::GLuint,
+        size: types::GLint,
+        type_: types::GLenum,
+        normalized: types::GLboolean,
+        stride: types::GLsizei,
+        pointer: *const c_void,
+    ) {
+        self.assert_attrib_pointer("glVertexAttribPointer");
+        self.inner
+            .VertexAttribPointer(index, size, type_, normalized, stride, pointer)
+    }
+
+    pub unsafe fn VertexAttribIPointer(
+        &self,
+        index: types::GLuint,
+        size: types::GLint,
+        type_: types::GLenum,
+        stride: types::GLsizei,
+        pointer: *const c_void,
+    ) {
+        self.assert_attrib_pointer("glVertexAttribIPointer");
+        self.inner
+            .VertexAttribIPointer(index, size, type_, stride, pointer)
+    }
+
+    pub unsafe fn DrawArrays(
+        &self,
+        mode: types::GLenum,
+        first: types::GLint,
+        count: types::GLsizei,
+    ) {
+        self.assert_draw("glDrawArrays");
+        self.inner.DrawArrays(mode, first, count)
+    }
+
+    pub unsafe fn DrawArraysInstanced(
+        &self,
+        mode: types::GLenum,
+        first: types::GLint,
+        count: types::GLsizei,
+        instancecount: types::GLsizei,
+    ) {
+        self.assert_draw("glDrawArraysInstanced");
+        self.inner
+            .DrawArraysInstanced(mode, first, count, instancecount)
+    }
+
+    pub unsafe fn DrawElements(
+        &self,
+        mode: types::GLenum,
+        count: types::GLsizei,
+        type_: types::GLenum,
+        indices: *const c_void,
+    ) {
+        self.assert_draw("glDrawElements");
+        self.inner.DrawElements(mode, count, type_, indices)
+    }
+
+    pub unsafe fn DrawElementsInstanced(
+        &self,
+        mode: types::GLenum,
+        count: types::GLsizei,
+        type_: types::GLenum,
+        indices: *const c_void,
+        instancecount: types::GLsizei,
+    ) {
+        self.assert_draw("glDrawElementsInstanced");
+        self.inner
+            .DrawElementsInstanced(mode, count, type_, indices, instancecount)
+    }
+
+    pub unsafe fn DrawArraysIndirect(&self, mode: types::GLenum, indirect: *const c_void) {
+        self.assert_draw_indirect("glDrawArraysIndirect", false);
+        self.inner.DrawArraysIndirect(mode, indirect)
+    }
+
+    pub unsafe fn DrawElementsIndirect(
+        &self,
+        mode: types::GLenum,
+        type_: types::GLenum,
+        indirect: *const c_void,
+    ) {
+        self.assert_draw_indirect("glDrawElementsIndirect", true);
+        self.inner.DrawElementsIndirect(mode, type_, indirect)
+    }
+
+    pub unsafe fn MultiDrawArraysIndirect(
+        &self,
+        mode: types::GLenum,
+        indirect: *const c_void,
+        drawcount: types::GLsizei,
+        stride: types::GLsizei,
+    ) {
+        self.assert_draw_indirect("glMultiDrawArraysIndirect", false);
+        self.inner
+            .MultiDrawArraysIndirect(mode, indirect, drawcount, stride)
+    }
+
+    pub unsafe fn MultiDrawElementsIndirect(
+        &self,
+        mode: types::GLenum,
+        type_: types::GLenum,
+        indirect: *const c_void,
+        drawcount: types::GLsizei,
+        stride: types::GLsizei,
+    ) {
+        self.assert_draw_indirect("glMultiDrawElementsIndirect", true);
+        self.inner
+            .MultiDrawElementsIndirect(mode, type_, indirect, drawcount, stride)
+    }
+
+    pub unsafe fn DispatchCompute(
+        &self,
+        num_groups_x: types::GLuint,
+        num_groups_y: types::GLuint,
+        num_groups_z: types::GLuint,
+    ) {
+        assert!(
+            self.state.borrow().program != 0,
+            "glDispatchCompute: no program is in use"
+        );
+        self.inner
+            .DispatchCompute(num_groups_x, num_groups_y, num_groups_z)
+    }
+
+    fn assert_attrib_pointer(&self, call: &str) {
+        let state = self.state.borrow();
+        assert!(
+            state.vertex_array != 0,
+            "{}: no vertex array is bound",
+            call
+        );
+        assert!(
+            state
+                .buffers
+                .get(&bindings::ARRAY_BUFFER)
+                .copied()
+                .unwrap_or(0)
+                != 0,
+            "{}: no array buffer is bound",
+            call
+        );
+    }
+
+    // Binding an indexed target also binds its generic binding point
+    fn track_indexed_buffer(
+        &self,
+        target: types::GLenum,
+        index: types::GLuint,
+        buffer: types::GLuint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        state.buffers.insert(target, buffer);
+        state.indexed_buffers.insert((target, index), buffer);
+    }
+
+    // Indirect draws read their parameters from the draw indirect buffer, indexed ones also
+    // need an element array buffer in the core profile
+    fn assert_draw_indirect(&self, call: &str, indexed: bool) {
+        self.assert_draw(call);
+        let state = self.state.borrow();
+        assert!(
+            state
+                .buffers
+                .get(&bindings::DRAW_INDIRECT_BUFFER)
+                .copied()
+                .unwrap_or(0)
+                != 0,
+            "{}: no draw indirect buffer is bound",
+            call
+        );
+        if indexed {
+            assert!(
+                state
+                    .element_buffers
+                    .get(&state.vertex_array)
+                    .copied()
+                    .unwrap_or(0)
+                    != 0,
+                "{}: vertex array {} has no element array buffer",
+                call,
+                state.vertex_array
+            );
+        }
+    }
+
+    fn assert_draw(&self, call: &str) {
+        let state = self.state.borrow();
+        assert!(state.program != 0, "{}: no program is in use", call);
+        assert!(
+            state.vertex_array != 0,
+            "{}: no vertex array is bound",
+            call
+        );
+    }
+}
+
+impl Deref for ValidatingGl {
+    type Target = bindings::Gl;
+
+    fn deref(&self) -> &bindings::Gl {
+        &self.inner
+    }
+}
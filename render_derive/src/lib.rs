@@ -12,18 +12,39 @@ extern crate syn;
 extern crate quote;
 
 // Procedural macros are declared by annotating a function with #[proc_macro_derive] or #[proc_macro_attribute].
-#[proc_macro_derive(VertexAttribPointers, attributes(location))]
+#[proc_macro_derive(
+    VertexAttribPointers,
+    attributes(location, divisor, normalized, integer, skip)
+)]
 pub fn vertex_attrib_pointers(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
     let ident = &input.ident;
     let generics = &input.generics;
     let where_clause = &generics.where_clause;
-    let fields_vertex_attrib_pointer = generate_vertex_attrib_pointer_calls(&input.data);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    // Mistakes are reported as compile errors pointing at the offending field or attribute
+    let generated = generate_vertex_attrib_pointer_calls(&input);
+    let (fields_vertex_attrib_pointer, layout, layout_len) = match generated {
+        Ok(generated) => generated,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    // Build the output, possibly using quasi-quotations
+    // Build the output, possibly using quasi-quotations. LAYOUT describes every attribute
+    // the vertex_attrib_pointers function sets up, both are also reachable through the
+    // render::Vertex trait. Locations taken by a field come from its VertexAttrib type, so
+    // LAYOUT is filled in a constant loop.
     proc_macro::TokenStream::from(quote! {
         impl #ident #generics #where_clause {
+            #[allow(unused_variables, unused_mut, unused_assignments)]
+            pub const LAYOUT: &'static [crate::render::data::AttributeDesc] = &{
+                const LEN: usize = 0 #(+ #layout_len)*;
+                let mut layout = [crate::render::data::AttributeDesc::float(0, 0, false); LEN];
+                let mut index = 0;
+                #(#layout)*
+                layout
+            };
+
             #[allow(unused_variables)]
             pub fn vertex_attrib_pointers(gl: &::gl::Gl) {
                 let stride = ::std::mem::size_of::<Self>();
@@ -32,70 +53,303 @@ pub fn vertex_attrib_pointers(input: proc_macro::TokenStream) -> proc_macro::Tok
                 #(#fields_vertex_attrib_pointer)*
             }
         }
+
+        impl #impl_generics crate::render::Vertex for #ident #ty_generics #where_clause {
+            fn layout() -> &'static [crate::render::data::AttributeDesc] {
+                Self::LAYOUT
+            }
+
+            fn setup_attribs(gl: &::gl::Gl) {
+                Self::vertex_attrib_pointers(gl)
+            }
+        }
     })
 }
 
-// Function which inspecting types with panic calls until we arrive at something reasonable
-fn generate_vertex_attrib_pointer_calls(data: &syn::Data) -> Vec<TokenStream> {
-    match data {
+// Function which inspects the struct fields and generates attribute pointer calls, statements
+// filling the layout and the number of layout entries of every field, errors of all fields
+// are combined so they are reported at once
+fn generate_vertex_attrib_pointer_calls(
+    input: &DeriveInput,
+) -> syn::Result<(Vec<TokenStream>, Vec<TokenStream>, Vec<TokenStream>)> {
+    let fields: Vec<&syn::Field> = match input.data {
         syn::Data::Struct(DataStruct {
             fields: syn::Fields::Named(ref fields),
             ..
-        }) => fields
-            .named
-            .iter()
-            .map(|f| generate_struct_field_vertex_attrib_pointer_call(f))
-            .collect(),
-        _ => panic!("#[derive(VertexAttribPointers)] is only defined for structs"),
+        }) => fields.named.iter().collect(),
+        // Tuple structs, e.g. struct P(VertVec3D, VertRGBA)
+        syn::Data::Struct(DataStruct {
+            fields: syn::Fields::Unnamed(ref fields),
+            ..
+        }) => fields.unnamed.iter().collect(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(VertexAttribPointers)] is only defined for structs with fields",
+            ))
+        }
+    };
+
+    // Fields without #[location] get the location after the previous field, starting at 0.
+    // It is a constant expression, as the number of locations of a field is only known to
+    // its type.
+    let mut next_location = quote! { 0 };
+    // Constant expression of the byte offset of the current field
+    let mut offset = quote! { 0 };
+    let mut calls = Vec::with_capacity(fields.len());
+    let mut layout = Vec::with_capacity(fields.len());
+    let mut layout_len = Vec::with_capacity(fields.len());
+    let mut errors: Option<syn::Error> = None;
+    for field in fields {
+        match generate_struct_field_vertex_attrib_pointer_call(
+            field,
+            &mut next_location,
+            &mut offset,
+        ) {
+            Ok((call, entries, locations)) => {
+                calls.push(call);
+                layout.push(entries);
+                layout_len.push(locations);
+            }
+            Err(e) => match errors {
+                Some(ref mut errors) => errors.combine(e),
+                None => errors = Some(e),
+            },
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok((calls, layout, layout_len)),
     }
 }
 
-fn generate_struct_field_vertex_attrib_pointer_call(field: &syn::Field) -> TokenStream {
-    let field_name = match field.ident {
-        Some(ref i) => format!("{}", i),
-        None => String::from(""),
+// Function which generates the attribute pointer call of a field, the statements filling its
+// layout entries and the number of entries
+fn generate_struct_field_vertex_attrib_pointer_call(
+    field: &syn::Field,
+    next_location: &mut TokenStream,
+    offset: &mut TokenStream,
+) -> syn::Result<(TokenStream, TokenStream, TokenStream)> {
+    let field_ty = &field.ty;
+    let field_offset = offset.clone();
+    *offset = quote! { #field_offset + ::std::mem::size_of::<#field_ty>() };
+
+    // Fields marked #[skip] (e.g. alignment padding) only move the offset of the next field,
+    // attributes describing the attribute of the field would have no effect
+    if field.attrs.iter().any(|a| a.path().is_ident("skip")) {
+        let mut errors: Option<syn::Error> = None;
+        let ignored = ["location", "divisor", "normalized", "integer"];
+        for attr in &field.attrs {
+            if let Some(name) = ignored.iter().find(|name| attr.path().is_ident(name)) {
+                let message = format!("a #[skip] field can not have #[{}]", name);
+                let e = syn::Error::new_spanned(attr, message);
+                match errors {
+                    Some(ref mut errors) => errors.combine(e),
+                    None => errors = Some(e),
+                }
+            }
+        }
+        if let Some(errors) = errors {
+            return Err(errors);
+        }
+
+        let call = quote! {
+            let offset = offset + ::std::mem::size_of::<#field_ty>();
+        };
+        return Ok((call, TokenStream::new(), quote! { 0 }));
+    }
+
+    // Explicit location, also continues numbering of following fields from it
+    let location_value = match field.attrs.iter().find(|a| a.path().is_ident("location")) {
+        Some(location_attr) => {
+            let location: usize = attr_int(location_attr, "location")?;
+            let location = proc_macro2::Literal::usize_unsuffixed(location);
+            quote! { #location }
+        }
+        None => next_location.clone(),
     };
 
-    let location_attr = field
-        .attrs
-        .iter()
-        .filter(|a| a.path().is_ident("location"))
-        .next()
-        .unwrap_or_else(|| panic!("Field {} is missing #[location = ?] attribute", field_name));
-
-    let location_value: usize = match location_attr.meta {
-        syn::Meta::NameValue(MetaNameValue { value: ref val, .. }) => expr_to_usize(val),
-        _ => panic!(
-            "Field {} location attribute value must be a string literal",
-            field_name
-        ),
+    // Raw integer fields take one location, data types as many as their VertexAttrib says
+    // (e.g. one per matrix column)
+    let normalized = field.attrs.iter().find(|a| a.path().is_ident("normalized"));
+    let integer = field.attrs.iter().find(|a| a.path().is_ident("integer"));
+    let locations = if normalized.is_some() || integer.is_some() {
+        quote! { 1 }
+    } else {
+        quote! { <#field_ty as crate::render::data::VertexAttrib>::LOCATIONS }
     };
+    *next_location = quote! { (#location_value) + #locations };
 
-    let field_ty = &field.ty;
+    // Optional divisor, attributes with divisor N advance once per N instances
+    let divisor_call = match field.attrs.iter().find(|a| a.path().is_ident("divisor")) {
+        Some(divisor_attr) => {
+            let divisor_value: u32 = attr_int(divisor_attr, "divisor")?;
+            // Matrices set the divisor of every column location
+            Some(quote! {
+                for column in 0..#locations {
+                    gl.VertexAttribDivisor((location + column) as ::gl::types::GLuint, #divisor_value);
+                }
+            })
+        }
+        None => None,
+    };
 
-    TokenStream::from(quote! {
+    // Raw integer fields choose float conversion with #[normalized] or stay integers with
+    // #[integer], other fields use the vertex_attrib_pointer function of their type
+    let (attrib_pointer_call, layout) = match (normalized, integer) {
+        (None, None) => {
+            // Layout of a data type is its ATTRIBUTE moved to the field, matrices repeat it for
+            // every column
+            let layout = quote! {
+                let mut column = 0;
+                while column < #locations {
+                    layout[index] = crate::render::data::AttributeDesc {
+                        location: ((#location_value) + column) as u32,
+                        offset: #field_offset
+                            + column * (::std::mem::size_of::<#field_ty>() / #locations),
+                        ..<#field_ty>::ATTRIBUTE
+                    };
+                    index += 1;
+                    column += 1;
+                }
+            };
+            let call = quote! {
+                #field_ty::vertex_attrib_pointer(gl, stride, location, offset);
+            };
+            (call, layout)
+        }
+        (Some(_), Some(integer_attr)) => {
+            return Err(syn::Error::new_spanned(
+                integer_attr,
+                "a field can not be both #[normalized] and #[integer]",
+            ))
+        }
+        _ => {
+            let (component_type, components) = raw_component_type(field_ty)?;
+            let pointer_call = if integer.is_some() {
+                quote! {
+                    gl.VertexAttribIPointer(
+                        location as ::gl::types::GLuint,
+                        #components,
+                        #component_type,
+                        stride as ::gl::types::GLint,
+                        offset as *const ::gl::types::GLvoid,
+                    );
+                }
+            } else {
+                quote! {
+                    gl.VertexAttribPointer(
+                        location as ::gl::types::GLuint,
+                        #components,
+                        #component_type,
+                        ::gl::TRUE,
+                        stride as ::gl::types::GLint,
+                        offset as *const ::gl::types::GLvoid,
+                    );
+                }
+            };
+            let call = quote! {
+                gl.EnableVertexAttribArray(location as ::gl::types::GLuint);
+                #pointer_call
+            };
+            let is_normalized = normalized.is_some();
+            let is_integer = integer.is_some();
+            let layout = quote! {
+                layout[index] = crate::render::data::AttributeDesc {
+                    location: (#location_value) as u32,
+                    components: (#components) as i32,
+                    gl_type: #component_type,
+                    normalized: #is_normalized,
+                    integer: #is_integer,
+                    offset: #field_offset,
+                };
+                index += 1;
+            };
+            (call, layout)
+        }
+    };
+    let call = quote! {
         let location = #location_value;
         unsafe {
-            #field_ty::vertex_attrib_pointer(gl, stride, location, offset);
+            #attrib_pointer_call
+            #divisor_call
         }
         let offset = offset + ::std::mem::size_of::<#field_ty>();
-    })
+    };
+    // Each field fills its entries in a block of its own
+    Ok((call, quote! { { #layout } }, locations))
 }
 
-// Convert a syn::Expr to usize
-fn expr_to_usize(expr: &syn::Expr) -> usize {
-    syn::LitInt::new(&expr_to_string(expr), proc_macro2::Span::call_site())
-        .base10_parse()
-        .unwrap_or_else(|_| panic!("Expected integer literal 2"))
+// Get gl component type and count of a raw integer field, e.g. u8 or [i16; 4]
+fn raw_component_type(ty: &syn::Type) -> syn::Result<(TokenStream, syn::Expr)> {
+    let (element_ty, components) = match ty {
+        syn::Type::Array(array) => (&*array.elem, array.len.clone()),
+        _ => (ty, syn::parse_quote!(1)),
+    };
+
+    let component_type = match element_ty {
+        syn::Type::Path(path) if path.qself.is_none() => path
+            .path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+    let component_type = match component_type.as_str() {
+        "i8" => quote! { ::gl::BYTE },
+        "u8" => quote! { ::gl::UNSIGNED_BYTE },
+        "i16" => quote! { ::gl::SHORT },
+        "u16" => quote! { ::gl::UNSIGNED_SHORT },
+        "i32" => quote! { ::gl::INT },
+        "u32" => quote! { ::gl::UNSIGNED_INT },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "#[normalized] and #[integer] fields must be an integer type or an array of it",
+            ))
+        }
+    };
+
+    Ok((component_type, components))
 }
 
-// Convert a syn::Expr to a string
-fn expr_to_string(expr: &syn::Expr) -> String {
+// Get value of an attribute written as #[name = N] or #[name = "N"], N has to fit into T
+fn attr_int<T: TryFrom<u64>>(attr: &syn::Attribute, name: &str) -> syn::Result<T> {
+    match attr.meta {
+        syn::Meta::NameValue(MetaNameValue { value: ref val, .. }) => {
+            T::try_from(expr_to_u64(val)?).map_err(|_| {
+                syn::Error::new_spanned(
+                    val,
+                    format!(
+                        "#[{}] is out of range for {}",
+                        name,
+                        ::std::any::type_name::<T>()
+                    ),
+                )
+            })
+        }
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            format!("expected #[{} = N] with an integer N", name),
+        )),
+    }
+}
+
+// Convert a syn::Expr holding an integer or a string with an integer to u64
+fn expr_to_u64(expr: &syn::Expr) -> syn::Result<u64> {
     match expr {
         syn::Expr::Lit(syn::ExprLit {
             lit: syn::Lit::Str(ref s),
             ..
-        }) => s.value(),
-        _ => panic!("Unexpected string literal"),
+        }) => s
+            .value()
+            .parse()
+            .map_err(|_| syn::Error::new_spanned(s, "expected a non-negative integer")),
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(ref i),
+            ..
+        }) => i.base10_parse(),
+        _ => Err(syn::Error::new_spanned(expr, "expected an integer literal")),
     }
 }
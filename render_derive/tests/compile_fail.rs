@@ -0,0 +1,7 @@
+// Check that mistakes in #[derive(VertexAttribPointers)] are reported as compile errors
+// pointing at the offending field or attribute, expected output is in tests/ui/*.stderr
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+use render_derive::VertexAttribPointers;
+
+#[derive(VertexAttribPointers)]
+struct Instance {
+    #[divisor = "4294967296"]
+    offset: f32,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use render_derive::VertexAttribPointers;
+
+#[derive(VertexAttribPointers)]
+enum Vertex {
+    Position,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use render_derive::VertexAttribPointers;
+
+#[derive(VertexAttribPointers)]
+struct Vertex {
+    #[location = "position"]
+    pos: f32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use render_derive::VertexAttribPointers;
+
+#[derive(VertexAttribPointers)]
+struct Vertex {
+    #[location = 1.5]
+    pos: f32,
+    #[integer]
+    color: [f32; 4],
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use render_derive::VertexAttribPointers;
+
+#[derive(VertexAttribPointers)]
+struct Vertex {
+    #[normalized]
+    #[integer]
+    color: [u8; 4],
+}
+
+fn main() {}
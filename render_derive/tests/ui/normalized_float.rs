@@ -0,0 +1,9 @@
+use render_derive::VertexAttribPointers;
+
+#[derive(VertexAttribPointers)]
+struct Vertex {
+    #[normalized]
+    weight: f32,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use render_derive::VertexAttribPointers;
+
+#[derive(VertexAttribPointers)]
+struct Vertex {
+    pos: f32,
+    #[skip]
+    #[location = 3]
+    #[divisor = 1]
+    _padding: f32,
+}
+
+fn main() {}
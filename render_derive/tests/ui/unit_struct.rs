@@ -0,0 +1,6 @@
+use render_derive::VertexAttribPointers;
+
+#[derive(VertexAttribPointers)]
+struct Vertex;
+
+fn main() {}
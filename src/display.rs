@@ -0,0 +1,124 @@
+// Import dependencies
+use sdl2::rect::Rect;
+use sdl2::video::{FullscreenType, Window};
+use sdl2::VideoSubsystem;
+
+// Struct that represents a resolution and refresh rate a monitor supports
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DisplayMode {
+    pub width: i32,
+    pub height: i32,
+    // Refresh rate in Hz, 0 if unknown
+    pub refresh_rate: i32,
+    // SDL pixel format, kept so the mode can be handed back to SDL unchanged
+    format: sdl2::pixels::PixelFormatEnum,
+}
+
+// Implement From trait to convert SDL display mode
+impl From<sdl2::video::DisplayMode> for DisplayMode {
+    fn from(mode: sdl2::video::DisplayMode) -> Self {
+        DisplayMode {
+            width: mode.w,
+            height: mode.h,
+            refresh_rate: mode.refresh_rate,
+            format: mode.format,
+        }
+    }
+}
+
+// Implement From trait to convert back to SDL display mode
+impl From<DisplayMode> for sdl2::video::DisplayMode {
+    fn from(mode: DisplayMode) -> Self {
+        sdl2::video::DisplayMode::new(mode.format, mode.width, mode.height, mode.refresh_rate)
+    }
+}
+
+// Struct that represents a connected monitor
+#[derive(Clone, Debug)]
+pub struct Monitor {
+    // SDL display index
+    pub index: i32,
+    pub name: String,
+    // Position and size on the desktop
+    pub bounds: Rect,
+    // Mode the desktop currently uses
+    pub current_mode: DisplayMode,
+    // Supported modes, SDL lists them from largest to smallest
+    pub modes: Vec<DisplayMode>,
+}
+
+// Implementation of monitor
+impl Monitor {
+    // Function to find the supported mode closest to the given resolution and refresh rate
+    // (0 refresh rate means the desktop one)
+    pub fn closest_mode(&self, width: i32, height: i32, refresh_rate: i32) -> Option<DisplayMode> {
+        let refresh_rate = match refresh_rate {
+            0 => self.current_mode.refresh_rate,
+            _ => refresh_rate,
+        };
+
+        self.modes.iter().copied().min_by_key(|mode| {
+            (
+                (mode.width - width).abs() + (mode.height - height).abs(),
+                (mode.refresh_rate - refresh_rate).abs(),
+            )
+        })
+    }
+}
+
+// Function to enumerate connected monitors with their supported modes
+pub fn monitors(video: &VideoSubsystem) -> Result<Vec<Monitor>, String> {
+    (0..video.num_video_displays()?)
+        .map(|index| {
+            let modes = (0..video.num_display_modes(index)?)
+                .map(|mode_index| video.display_mode(index, mode_index).map(DisplayMode::from))
+                .collect::<Result<Vec<DisplayMode>, String>>()?;
+
+            Ok(Monitor {
+                index,
+                name: video.display_name(index)?,
+                bounds: video.display_bounds(index)?,
+                current_mode: video.current_display_mode(index)?.into(),
+                modes,
+            })
+        })
+        .collect()
+}
+
+// Function to switch window to exclusive fullscreen on the given monitor, using the given
+// mode or the desktop mode of the monitor
+pub fn set_fullscreen(
+    window: &mut Window,
+    monitor: &Monitor,
+    mode: Option<DisplayMode>,
+) -> Result<(), String> {
+    // Fullscreen uses the display the window is on, so move it there first
+    window.set_fullscreen(FullscreenType::Off)?;
+    window.set_position(
+        sdl2::video::WindowPos::Positioned(monitor.bounds.x()),
+        sdl2::video::WindowPos::Positioned(monitor.bounds.y()),
+    );
+
+    let mode = mode.unwrap_or(monitor.current_mode);
+    window.set_display_mode(Some(mode.into()))?;
+    window.set_fullscreen(FullscreenType::True)
+}
+
+// Function to switch window to borderless fullscreen at the desktop resolution, or back
+// to windowed mode
+pub fn set_desktop_fullscreen(window: &mut Window, enabled: bool) -> Result<(), String> {
+    window.set_fullscreen(if enabled {
+        FullscreenType::Desktop
+    } else {
+        FullscreenType::Off
+    })
+}
+
+// Function to get refresh rate in Hz of the display the window is on, None if unknown
+// (e.g. to derive the frame limiter target)
+pub fn refresh_rate(window: &Window) -> Option<i32> {
+    match window.display_mode() {
+        Ok(mode) if mode.refresh_rate > 0 => Some(mode.refresh_rate),
+        _ => None,
+    }
+}
@@ -0,0 +1,200 @@
+// Import dependencies
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(
+        display = "Image of {}x{} is not a cubemap cross (4x3 or 3x4 square faces)",
+        width, height
+    )]
+    NotACubemapCross { width: u32, height: u32 },
+}
+
+// Enum that represents which way the green channel (Y) of a tangent space normal map points
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NormalMapConvention {
+    // Green points up (+Y), what OpenGL, Blender and glTF expect
+    OpenGl,
+    // Green points down (-Y), e.g. maps exported for DirectX engines
+    DirectX,
+}
+
+// Function to compute the length of a buffer holding width * height pixels of the given
+// number of components, panics on overflow so callers never pass a short buffer to gl
+pub fn buffer_len(width: u32, height: u32, components: usize) -> usize {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(components))
+        .unwrap_or_else(|| panic!("Image of {}x{} pixels is too large", width, height))
+}
+
+// Function to resize image to the given size
+pub fn resize(image: &RgbaImage, width: u32, height: u32, filter: FilterType) -> RgbaImage {
+    imageops::resize(image, width, height, filter)
+}
+
+// Function to flip image upside down, e.g. between image rows (top first) and OpenGL rows
+// (bottom first)
+pub fn flip_vertical(image: &mut RgbaImage) {
+    imageops::flip_vertical_in_place(image);
+}
+
+// Function to mirror image left to right
+pub fn flip_horizontal(image: &mut RgbaImage) {
+    imageops::flip_horizontal_in_place(image);
+}
+
+// Function to multiply color channels by alpha, for blending with gl::ONE as source factor
+// and for filtering without dark fringes around transparent texels
+pub fn premultiply_alpha(image: &mut RgbaImage) {
+    for pixel in image.pixels_mut() {
+        let alpha = pixel[3] as u32;
+        for channel in &mut pixel.0[..3] {
+            *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+// Function to reorder channels, channel i of the result is channel order[i] of the source,
+// e.g. [2, 1, 0, 3] swaps BGRA and RGBA
+pub fn swizzle(image: &mut RgbaImage, order: [usize; 4]) {
+    assert!(order.iter().all(|&channel| channel < 4));
+
+    for pixel in image.pixels_mut() {
+        let source = pixel.0;
+        pixel.0 = order.map(|channel| source[channel]);
+    }
+}
+
+// Function to split a cubemap cross into its faces in OpenGL order (+X, -X, +Y, -Y, +Z, -Z).
+// Horizontal crosses (4x3 faces) have -X, +Z, +X, -Z in the middle row, vertical crosses
+// (3x4 faces) have -Z upside down below -Y.
+pub fn split_cubemap_cross(image: &RgbaImage) -> Result<[RgbaImage; 6], Error> {
+    let (width, height) = image.dimensions();
+    let error = Error::NotACubemapCross { width, height };
+
+    // Face positions in the cross as (column, row)
+    let (size, cells, vertical) = if width * 3 == height * 4 && width % 4 == 0 {
+        let cells = [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)];
+        (width / 4, cells, false)
+    } else if width * 4 == height * 3 && width % 3 == 0 {
+        let cells = [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (1, 3)];
+        (width / 3, cells, true)
+    } else {
+        return Err(error);
+    };
+    if size == 0 {
+        return Err(error);
+    }
+
+    let mut faces = cells.map(|(column, row)| {
+        imageops::crop_imm(image, column * size, row * size, size, size).to_image()
+    });
+    if vertical {
+        faces[5] = imageops::rotate180(&faces[5]);
+    }
+
+    Ok(faces)
+}
+
+// Function to invert the green channel, converts normal maps between the DirectX and the
+// OpenGL convention
+pub fn flip_green(image: &mut RgbaImage) {
+    for pixel in image.pixels_mut() {
+        pixel[1] = 255 - pixel[1];
+    }
+}
+
+// Function to guess the convention of a tangent space normal map with rows bottom first (as
+// uploaded to OpenGL). Normals derived from a height field form a gradient field, which has
+// no curl in the right convention, while flipping green makes the curl show up as the sum
+// of the cross derivatives. Flat maps without detail are reported as OpenGL.
+pub fn detect_normal_map_convention(image: &RgbaImage) -> NormalMapConvention {
+    let component = |x: u32, y: u32, channel: usize| image.get_pixel(x, y)[channel] as f32;
+    let (width, height) = image.dimensions();
+
+    let mut opengl_curl = 0.0;
+    let mut directx_curl = 0.0;
+    for y in 0..height.saturating_sub(1) {
+        for x in 0..width.saturating_sub(1) {
+            // Cross derivatives across a 2x2 cell in channel units, only their ratio matters
+            let dx_dy = component(x, y + 1, 0) + component(x + 1, y + 1, 0)
+                - component(x, y, 0)
+                - component(x + 1, y, 0);
+            let dy_dx = component(x + 1, y, 1) + component(x + 1, y + 1, 1)
+                - component(x, y, 1)
+                - component(x, y + 1, 1);
+            opengl_curl += (dx_dy - dy_dx).abs();
+            directx_curl += (dx_dy + dy_dx).abs();
+        }
+    }
+
+    if directx_curl < opengl_curl {
+        NormalMapConvention::DirectX
+    } else {
+        NormalMapConvention::OpenGl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    // Function to create an OpenGL convention normal map from a bumpy height field, rows
+    // bottom first
+    fn bumpy_normal_map(size: u32) -> RgbaImage {
+        let frequency = std::f32::consts::TAU / size as f32 * 2.0;
+        RgbaImage::from_fn(size, size, |x, y| {
+            let (x, y) = (x as f32 * frequency, y as f32 * frequency);
+            // Gradient of sin(x) * sin(y) scaled to a strong slope
+            let dh_dx = 2.0 * x.cos() * y.sin();
+            let dh_dy = 2.0 * x.sin() * y.cos();
+            let length = (dh_dx * dh_dx + dh_dy * dh_dy + 1.0).sqrt();
+            let encode = |value: f32| ((value / length * 0.5 + 0.5) * 255.0).round() as u8;
+            Rgba([encode(-dh_dx), encode(-dh_dy), encode(1.0), 255])
+        })
+    }
+
+    #[test]
+    fn detects_opengl_normal_map() {
+        let image = bumpy_normal_map(32);
+        assert_eq!(
+            detect_normal_map_convention(&image),
+            NormalMapConvention::OpenGl
+        );
+    }
+
+    #[test]
+    fn detects_directx_normal_map() {
+        let mut image = bumpy_normal_map(32);
+        flip_green(&mut image);
+        assert_eq!(
+            detect_normal_map_convention(&image),
+            NormalMapConvention::DirectX
+        );
+    }
+
+    #[test]
+    fn buffer_len_multiplies_dimensions() {
+        assert_eq!(buffer_len(3, 2, 4), 24);
+        assert_eq!(buffer_len(0, 7, 4), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn buffer_len_panics_on_overflow() {
+        buffer_len(u32::MAX, u32::MAX, 4);
+    }
+
+    #[test]
+    fn flat_normal_map_is_opengl() {
+        let image = RgbaImage::from_pixel(8, 8, Rgba([128, 128, 255, 255]));
+        assert_eq!(
+            detect_normal_map_convention(&image),
+            NormalMapConvention::OpenGl
+        );
+    }
+}
@@ -1,8 +1,8 @@
 // Import dependencies
 use failure::err_msg;
-use render::buffer;
 use render::data;
 use resources::Resources;
+#[cfg(not(feature = "embed_assets"))]
 use std::path::Path;
 
 // Import failure crate to handle errors
@@ -20,10 +20,27 @@ extern crate sdl2; // SDL2
 // Extern crate for vertex attribute pointers
 extern crate vec_2_10_10_10;
 
+// Import display module from src/display.rs
+pub mod display;
+// Import image_util module from src/image_util.rs
+pub mod image_util;
+// Import math module from src/math.rs
+pub mod math;
+// Import random module from src/random.rs
+pub mod random;
 // Import render module from src/render.rs
 pub mod render;
 // Import resources module from src/resources.rs
 pub mod resources;
+// Import time module from src/time.rs
+pub mod time;
+// Import window module from src/window.rs
+pub mod window;
+
+// Title of the application window
+const WINDOW_TITLE: &str = "OpenGL Window - Rust";
+// Samples per pixel of the window framebuffer, 0 disables multisampling
+const MSAA_SAMPLES: u8 = 4;
 
 // Define a vertex struct with position and color
 #[derive(VertexAttribPointers, Copy, Clone, Debug)]
@@ -35,6 +52,115 @@ struct Vertex {
     color: data::VertRGBA,
 }
 
+// Struct that holds GPU resources of the application, rebuilt when the context is lost
+struct Scene {
+    shader_program: render::Program,
+    triangle: render::mesh::Mesh,
+    // Procedural sky drawn as background
+    sky: render::sky::Sky,
+    // Uniform buffer feeding the Time block of every program
+    time_buffer: render::buffer::UniformBuffer,
+    uniform_bindings: render::buffer::UniformBindings,
+    // Asynchronous pixel readbacks, e.g. screenshots
+    readback: render::readback::ReadbackManager,
+}
+
+// Implementation of scene
+impl Scene {
+    // Function to create GPU resources from assets
+    fn new(
+        gl: &gl::Gl,
+        res: &Resources,
+        extensions: &render::extensions::Extensions,
+    ) -> Result<Scene, failure::Error> {
+        // Create shaders from vertex and fragment sources
+        // Linking shaders into program, or reuse the binary linked on a previous run
+        let cache = render::program_cache::ProgramCache::from_relative_exe_path(
+            gl,
+            std::path::Path::new("shader_cache"),
+        )?;
+        // SPIR-V binaries of the stages are only used if the driver can load them
+        let specialization = render::Specialization::new().spirv_supported(extensions.has_spirv());
+        let shader_program =
+            render::Program::from_res_cached(gl, res, "shaders/triangle", &specialization, &cache)
+                .map_err(err_msg)?;
+
+        // Create vertex array object with vertecies
+        let vertices: Vec<Vertex> = vec![
+            // positions      // colors
+            Vertex {
+                pos: (0.5, -0.5, 0.0).into(),
+                color: (1.0, 0.0, 0.0, 1.0).into(),
+            }, // bottom right
+            Vertex {
+                pos: (-0.5, -0.5, 0.0).into(),
+                color: (0.0, 1.0, 0.0, 1.0).into(),
+            }, // bottom left
+            Vertex {
+                pos: (0.0, 0.5, 0.0).into(),
+                color: (0.0, 0.0, 1.0, 1.0).into(),
+            }, // top
+        ];
+        // Upload vertices into a mesh, attribute layout comes from the derived Vertex trait
+        let triangle = render::mesh::Mesh::new(gl, &vertices);
+        let sky = render::sky::Sky::from_res(gl, res).map_err(err_msg)?;
+
+        // Bind the time buffer once, programs are connected to its binding point every frame
+        let mut uniform_bindings = render::buffer::UniformBindings::new(0);
+        let time_buffer = render::buffer::UniformBuffer::new(gl);
+        time_buffer.bind();
+        time_buffer.allocate(
+            std::mem::size_of::<time::TimeBlock>(),
+            render::buffer::Usage::DynamicDraw,
+        );
+        time_buffer.unbind();
+        time_buffer.bind_base(uniform_bindings.binding(time::TIME_BLOCK));
+
+        Ok(Scene {
+            shader_program,
+            triangle,
+            sky,
+            time_buffer,
+            uniform_bindings,
+            readback: render::readback::ReadbackManager::new(gl),
+        })
+    }
+
+    // Function to upload per frame data shared by all programs
+    fn upload_frame(&self, time: &time::Time) {
+        self.time_buffer.bind();
+        self.time_buffer.update_sub_data(0, &[time.block()]);
+        self.time_buffer.unbind();
+    }
+}
+
+// Function to set context state shared by all draws
+fn init_gl_state(gl: &gl::Gl, (width, height): (u32, u32)) {
+    render::texture::enable_seamless_cubemap(gl);
+    unsafe {
+        gl.Enable(gl::MULTISAMPLE);
+        gl.Viewport(0, 0, width as i32, height as i32); // set viewport
+        gl.ClearColor(0.24, 0.7, 0.5, 1.0);
+    }
+    // Depth test nearer fragments, passes which need other settings use a StateScope
+    render::state::DepthStencilState::new().apply(gl);
+}
+
+// Function to create the window with the OpenGL attributes set on the video subsystem
+fn create_window(
+    video_subsystem: &sdl2::VideoSubsystem,
+) -> Result<(sdl2::video::Window, sdl2::video::GLContext), failure::Error> {
+    let window = video_subsystem
+        .window(WINDOW_TITLE, 800, 700)
+        .opengl() // Add OpenGL flag
+        .resizable()
+        .position_centered()
+        .build()?;
+    // Create OpenGL context
+    let gl_context = window.gl_create_context().map_err(err_msg)?;
+    Ok((window, gl_context))
+}
+
 // Entry point function
 fn main() {
     if let Err(e) = run() {
@@ -45,7 +171,28 @@ fn main() {
 
 // Function which handles the main loop of the program
 fn run() -> Result<(), failure::Error> {
+    // Seed for random generators, logged so a run can be reproduced with --seed
+    let seed = random::seed_from_args(std::env::args().skip(1))?;
+    let seed = seed.unwrap_or_else(random::seed_from_time);
+    println!("Random seed: {0} (reproduce with --seed {0})", seed);
+    let mut rng = random::Rng::new(seed);
+
+    // Debug builds load assets straight from the source tree and watch them for changes,
+    // so shaders can be edited while the application is running
+    #[cfg(all(debug_assertions, not(feature = "embed_assets")))]
+    let res = {
+        let mut res = Resources::from_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("assets"));
+        if let Err(e) = res.watch() {
+            eprintln!("Shader hot-reload is disabled: {}", e);
+        }
+        res
+    };
+    #[cfg(all(not(debug_assertions), not(feature = "embed_assets")))]
     let res = Resources::from_relative_exe_path(Path::new("assets")).map_err(err_msg)?;
+    // With embed_assets feature assets are compiled into the executable, so it can be
+    // shipped without the assets directory
+    #[cfg(feature = "embed_assets")]
+    let res = Resources::from_embedded(resources::embedded::ASSETS);
 
     // Initialize SDL2
     let sdl = sdl2::init().map_err(err_msg)?;
@@ -57,62 +204,74 @@ fn run() -> Result<(), failure::Error> {
     // Set OpenGL version to 4.5
     gl_attr.set_context_profile(sdl2::video::GLProfile::Core);
     gl_attr.set_context_version(4, 5);
+    // Antialias the window framebuffer, offscreen targets use Framebuffer::new_multisample
+    window::set_gl_multisample(&video_subsystem, MSAA_SAMPLES);
+    // Request a depth buffer for the window framebuffer
+    gl_attr.set_depth_size(24);
 
-    // Create a window
-    let window = video_subsystem
-        .window("OpenGL Window - Rust", 800, 700)
-        .opengl() // Add OpenGL flag
-        .resizable()
-        .position_centered()
-        .build()?;
-    // Create OpenGL context
-    let _gl_context = window.gl_create_context().map_err(err_msg)?;
+    // Request a robust context which reports driver resets instead of crashing, so the
+    // application can recreate its GPU resources (see recovery in the main loop)
+    gl_attr.set_context_flags().robust_access().set();
+    unsafe {
+        sdl2::sys::SDL_GL_SetAttribute(
+            sdl2::sys::SDL_GLattr::SDL_GL_CONTEXT_RESET_NOTIFICATION,
+            sdl2::sys::SDL_GLContextResetNotification::SDL_GL_CONTEXT_RESET_LOSE_CONTEXT as i32,
+        );
+    }
+
+    // Create a window and its OpenGL context. Not every driver and display offers
+    // multisampled window framebuffers, so retry without antialiasing before giving up.
+    let (mut window, mut gl_context) = match create_window(&video_subsystem) {
+        Ok(created) => created,
+        Err(e) => {
+            eprintln!("No multisampled window ({}), retrying without", e);
+            window::set_gl_multisample(&video_subsystem, 0);
+            create_window(&video_subsystem)?
+        }
+    };
     // Load OpenGL function pointers
-    let gl = gl::Gl::load_with(|s| {
+    let mut gl = gl::Gl::load_with(|s| {
         video_subsystem.gl_get_proc_address(s) as *const std::os::raw::c_void
     });
+    // Query optional features of the context
+    let mut extensions = render::extensions::Extensions::new(&gl);
+    // Resets can only be detected if the driver honoured the robustness request
+    let mut robust = extensions.has_robustness() && render::robustness::is_robust(&gl);
 
-    // Create shaders from vertex and fragment sources
-    // Linking shaders into program
-    let shader_program =
-        render::Program::from_res(&gl, &res, "shaders/triangle").map_err(err_msg)?;
-
-    // Create vertex array object with vertecies
-    let vertices: Vec<Vertex> = vec![
-        // positions      // colors
-        Vertex {
-            pos: (0.5, -0.5, 0.0).into(),
-            color: (1.0, 0.0, 0.0, 1.0).into(),
-        }, // bottom right
-        Vertex {
-            pos: (-0.5, -0.5, 0.0).into(),
-            color: (0.0, 1.0, 0.0, 1.0).into(),
-        }, // bottom left
-        Vertex {
-            pos: (0.0, 0.5, 0.0).into(),
-            color: (0.0, 0.0, 1.0, 1.0).into(),
-        }, // top
-    ];
-    // Request OpenGL to give one buffer name (as integer),
-    // and write it into vertex buffer object (vbo)
-    let vbo = buffer::ArrayBuffer::new(&gl);
-    vbo.bind();
-    vbo.static_draw_data(&vertices);
-    vbo.unbind();
-
-    // Create vertex array object
-    let vao = buffer::VertexArray::new(&gl);
-    vao.bind();
-    vbo.bind();
-    Vertex::vertex_attrib_pointers(&gl);
-    vbo.unbind();
-    vao.unbind();
+    // Create GPU resources
+    let mut scene = Scene::new(&gl, &res, &extensions)?;
+
+    // Create camera looking at the triangle from the front
+    let mut camera = render::camera::Camera::perspective(
+        std::f32::consts::FRAC_PI_4,                     // vertical field of view
+        window.size().0 as f32 / window.size().1 as f32, // aspect ratio
+        0.1,                                             // near plane
+        100.0,                                           // far plane
+    );
+    camera.look_at(
+        math::Vec3::new(0.0, 0.0, 2.0), // position
+        math::Vec3::ZERO,               // target
+        math::Vec3::Y,                  // up
+    );
 
     // Set shared state for window
-    unsafe {
-        gl.Viewport(0, 0, window.size().0 as i32, window.size().1 as i32); // set viewport
-        gl.ClearColor(0.24, 0.7, 0.5, 1.0);
-    }
+    init_gl_state(&gl, window.size());
+
+    // Show frame rate in the window title
+    let mut title_fps = window::TitleFps::new(WINDOW_TITLE);
+
+    // Frame time fed to shaders, P pauses it and [ / ] halve or double its speed
+    let mut time = time::Time::new();
+    // Day/night cycle moving the sun of the sky, one day lasts two minutes
+    let mut day_night = render::day_night::DayNight::new(120.0);
+    // Start at a random daytime with random haze, reproducible from the seed
+    let mut sky_rng = rng.fork();
+    day_night.set_time_of_day(sky_rng.range_f32(0.25, 0.75));
+    day_night.set_turbidity(sky_rng.range_f32(2.0, 4.0));
+
+    // Screenshots requested with F12, saved once their pixels arrive a few frames later
+    let mut take_screenshot = false;
+    let mut screenshots = Vec::new();
 
     'main: loop {
         // Handle events
@@ -124,43 +283,154 @@ fn run() -> Result<(), failure::Error> {
                     keycode: Some(sdl2::keyboard::Keycode::Escape),
                     ..
                 } => break 'main,
+                // Copy camera pose and GL info to the clipboard, e.g. for bug reports
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(sdl2::keyboard::Keycode::F2),
+                    ..
+                } => {
+                    let (major, minor) = extensions.version();
+                    let info = format!(
+                        "camera position: {}, target: {}, up: {}\nOpenGL {}.{}\nseed: {}",
+                        camera.position(),
+                        camera.target(),
+                        camera.up(),
+                        major,
+                        minor,
+                        seed,
+                    );
+                    if let Err(e) = window::set_clipboard_text(&video_subsystem, &info) {
+                        eprintln!("{}", failure_to_string(e.into()));
+                    }
+                }
+                // Pause or resume animated time
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(sdl2::keyboard::Keycode::P),
+                    ..
+                } => time.set_paused(!time.is_paused()),
+                // Slow down or speed up animated time
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(sdl2::keyboard::Keycode::LeftBracket),
+                    ..
+                } => time.set_scale(time.scale() / 2.0),
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(sdl2::keyboard::Keycode::RightBracket),
+                    ..
+                } => time.set_scale(time.scale() * 2.0),
+                // Save the next frame as a PNG in the working directory
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(sdl2::keyboard::Keycode::F12),
+                    ..
+                } => take_screenshot = true,
                 // Update window viewport after resize event
                 sdl2::event::Event::Window { win_event, .. } => match win_event {
-                    sdl2::event::WindowEvent::Resized(width, height) => unsafe {
-                        gl.Viewport(0, 0, width, height);
-                    },
+                    sdl2::event::WindowEvent::Resized(width, height) => {
+                        unsafe {
+                            gl.Viewport(0, 0, width, height);
+                        }
+                        camera.resize(width, height);
+                    }
                     _ => {}
                 },
                 _ => {}
             }
         }
 
-        // Clear the screen to the background color
+        // Advance frame time and share it with shaders
+        time.tick();
+        scene.upload_frame(&time);
+        day_night.update(&time);
+        day_night.apply(&mut scene.sky);
+
+        // Clear the screen to the background color and the depth buffer to the far plane
         unsafe {
-            gl.Clear(gl::COLOR_BUFFER_BIT);
+            gl.Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
         }
 
+        // Rebuild the shader program if its sources changed, old program is kept on failure
+        if let Err(e) = scene.shader_program.reload_if_changed(&res) {
+            eprintln!("{}", failure_to_string(e.into()));
+        }
+        if let Err(e) = scene.sky.reload_if_changed(&res) {
+            eprintln!("{}", failure_to_string(e.into()));
+        }
+
+        // Draw sky as background
+        scene.sky.draw(&camera);
+
+        // Connect shared blocks, a reloaded program starts without block bindings
+        scene.uniform_bindings.apply(&scene.shader_program);
+
         // Set the shader program as used
-        shader_program.set_used();
+        scene.shader_program.set_used();
+        camera.upload_uniforms(&scene.shader_program);
 
         // Draw triangle
-        vao.bind();
-        unsafe {
-            // Bind the vertex array object
-            gl.DrawArrays(
-                gl::TRIANGLES, // mode
-                0,             // starting index in the enabled arrays
-                3,             // number of indices to be rendered
-            );
+        scene.triangle.draw();
+
+        // Copy the finished frame before it is swapped away
+        if take_screenshot {
+            take_screenshot = false;
+            let (width, height) = window.drawable_size();
+            screenshots.push(scene.readback.read_pixels(0, 0, width, height));
         }
 
         // Swap the window
         window.gl_swap_window();
+
+        // Save screenshots whose pixels have arrived
+        scene.readback.poll();
+        screenshots.retain(|receiver| match receiver.try_recv() {
+            Ok(image) => {
+                save_screenshot(image);
+                false
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => true,
+            // Readback was dropped with a lost context
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => false,
+        });
+        title_fps.frame(&mut window)?;
+
+        // Recover from a driver reset: all objects of the lost context are gone, so the
+        // context is recreated and GPU resources are rebuilt from the cached assets
+        let status = if robust {
+            render::robustness::reset_status(&gl)
+        } else {
+            render::robustness::ResetStatus::NoError
+        };
+        if status.is_lost() {
+            eprintln!("OpenGL context was lost ({:?}), recreating it", status);
+
+            drop(scene);
+            drop(gl_context);
+            gl_context = window.gl_create_context().map_err(err_msg)?;
+            gl = gl::Gl::load_with(|s| {
+                video_subsystem.gl_get_proc_address(s) as *const std::os::raw::c_void
+            });
+            extensions = render::extensions::Extensions::new(&gl);
+            robust = extensions.has_robustness() && render::robustness::is_robust(&gl);
+
+            scene = Scene::new(&gl, &res, &extensions)?;
+            init_gl_state(&gl, window.size());
+        }
     }
 
     Ok(())
 }
 
+// Function to save a screenshot as screenshot-<unix time in ms>.png in the working directory,
+// encoding and writing happen on a thread of their own so the frame is not stalled
+fn save_screenshot(image: image::RgbaImage) {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    let path = format!("screenshot-{}.png", millis);
+    std::thread::spawn(move || match image.save(&path) {
+        Ok(()) => println!("Saved {}", path),
+        Err(e) => eprintln!("Failed to save {}: {}", path, e),
+    });
+}
+
 // Function that takes any object that implements failure::Fail and prints out the chain of all causes:
 pub fn failure_to_string(e: failure::Error) -> String {
     use std::fmt::Write;
@@ -0,0 +1,2 @@
+// Re-export glam types so users of the crate build transforms with the same math types
+pub use glam::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
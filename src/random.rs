@@ -0,0 +1,224 @@
+// Import dependencies
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::math::Vec3;
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(
+        display = "Invalid seed {:?}, expected a number like --seed 1234",
+        value
+    )]
+    InvalidSeed { value: String },
+}
+
+// Command line option that fixes the seed, e.g. `--seed 1234` or `--seed=1234`
+const SEED_ARG: &str = "--seed";
+
+// Struct that represents a small pseudo random generator (PCG32), the same seed always
+// produces the same sequence so procedural output can be reproduced from a logged seed
+#[derive(Clone, Debug)]
+pub struct Rng {
+    // Seed the generator was created with
+    seed: u64,
+    state: u64,
+    // Stream selector, always odd
+    increment: u64,
+}
+
+// Implementation of rng
+impl Rng {
+    // Constructor for a generator with the given seed
+    pub fn new(seed: u64) -> Rng {
+        Rng::with_stream(seed, 0)
+    }
+
+    // Constructor for a generator with the given seed and stream, generators with the same
+    // seed but different streams produce independent sequences
+    pub fn with_stream(seed: u64, stream: u64) -> Rng {
+        let mut rng = Rng {
+            seed,
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    // Function to get the seed the generator was created with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Function to create a generator for a subsystem (e.g. one particle emitter), so adding
+    // random calls in one subsystem does not change the sequence of another
+    pub fn fork(&mut self) -> Rng {
+        let seed = self.next_u64();
+        let stream = self.next_u64();
+        Rng::with_stream(seed, stream)
+    }
+
+    // Function to get the next random u32
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(self.increment);
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    // Function to get the next random u64
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    // Function to get a random f32 in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        // 24 bits fit the f32 mantissa exactly
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    // Function to get a random bool
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u32() & 1 == 1
+    }
+
+    // Function to get a random f32 in [min, max)
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    // Function to get a random u32 in [min, max), min if the range is empty
+    pub fn range_u32(&mut self, min: u32, max: u32) -> u32 {
+        if max <= min {
+            return min;
+        }
+
+        // Reject values from the incomplete last block to avoid modulo bias
+        let range = max - min;
+        let threshold = range.wrapping_neg() % range;
+        loop {
+            let value = self.next_u32();
+            if value >= threshold {
+                return min + value % range;
+            }
+        }
+    }
+
+    // Function to pick a random element of the slice, None if it is empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        items.get(self.range_u32(0, items.len() as u32) as usize)
+    }
+
+    // Function to get a random point inside the unit sphere, e.g. for particle velocities
+    pub fn in_unit_sphere(&mut self) -> Vec3 {
+        loop {
+            let point = Vec3::new(
+                self.range_f32(-1.0, 1.0),
+                self.range_f32(-1.0, 1.0),
+                self.range_f32(-1.0, 1.0),
+            );
+            if point.length_squared() < 1.0 {
+                return point;
+            }
+        }
+    }
+
+    // Function to get a random direction of length 1
+    pub fn unit_vector(&mut self) -> Vec3 {
+        loop {
+            let point = self.in_unit_sphere();
+            if point.length_squared() > 1e-6 {
+                return point.normalize();
+            }
+        }
+    }
+}
+
+// Function to get seed given on the command line, None if there is none and an error if it
+// is missing its value or is not a number
+pub fn seed_from_args<I>(args: I) -> Result<Option<u64>, Error>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = if arg == SEED_ARG {
+            args.next().unwrap_or_default()
+        } else if let Some(value) = arg.strip_prefix(SEED_ARG).and_then(|a| a.strip_prefix('=')) {
+            value.to_owned()
+        } else {
+            continue;
+        };
+
+        return match value.parse() {
+            Ok(seed) => Ok(Some(seed)),
+            Err(_) => Err(Error::InvalidSeed { value }),
+        };
+    }
+
+    Ok(None)
+}
+
+// Function to get a seed that differs between runs
+pub fn seed_from_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut rng = Rng::new(42);
+        let sequence: Vec<u32> = (0..5).map(|_| rng.next_u32()).collect();
+        assert_eq!(
+            sequence,
+            [565663470, 3244226384, 2504567229, 903561869, 4026996297]
+        );
+
+        let mut first = Rng::new(1234);
+        let mut second = Rng::new(1234);
+        for _ in 0..100 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+    }
+
+    #[test]
+    fn seed_is_parsed_from_args() {
+        assert_eq!(
+            seed_from_args(args(&["--seed", "1234"])).unwrap(),
+            Some(1234)
+        );
+        assert_eq!(
+            seed_from_args(args(&["-v", "--seed=99"])).unwrap(),
+            Some(99)
+        );
+        assert_eq!(seed_from_args(args(&["-v"])).unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_seed_is_an_error() {
+        assert!(seed_from_args(args(&["--seed", "abc"])).is_err());
+        assert!(seed_from_args(args(&["--seed="])).is_err());
+        assert!(seed_from_args(args(&["--seed"])).is_err());
+    }
+}
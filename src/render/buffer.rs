@@ -1,11 +1,191 @@
 // Import dependencies
 use gl;
+use std::collections::HashMap;
+use std::mem::MaybeUninit;
+
+use crate::render::shader::validate_before_draw;
+use crate::render::{Program, Vertex};
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(display = "Offset {} is not aligned to {} bytes", offset, align)]
+    MisalignedOffset { offset: usize, align: usize },
+    #[fail(
+        display = "Failed to map {} bytes at offset {}, the range does not fit into the storage or the buffer is mapped",
+        size, offset
+    )]
+    MapFailed { offset: usize, size: usize },
+}
 
 // Trait to represent the buffer type
 pub trait BufferType {
     const BUFFER_TYPE: gl::types::GLuint;
 }
 
+// Trait to represent data that can be uploaded into a buffer of type B, array buffers only
+// take Vertex types so their attribute layout is known
+pub trait BufferData<B> {}
+
+impl<V: Vertex> BufferData<BufferTypeArray> for V {}
+impl<T> BufferData<BufferTypeElementArray> for T {}
+impl<T> BufferData<BufferTypeUniform> for T {}
+impl<T> BufferData<BufferTypeShaderStorage> for T {}
+impl<T> BufferData<BufferTypePixelPack> for T {}
+
+/// Trait to represent plain old data, types for which any bit pattern is a valid value so the
+/// gpu can write them (e.g. in get_sub_data)
+///
+/// # Safety
+///
+/// Implementors have to be Copy, contain no padding, pointers or references and accept every
+/// bit pattern, e.g. #[repr(C)] structs made only of Pod fields without padding between them
+pub unsafe trait Pod: Copy + 'static {}
+
+unsafe impl Pod for u8 {}
+unsafe impl Pod for i8 {}
+unsafe impl Pod for u16 {}
+unsafe impl Pod for i16 {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for i32 {}
+unsafe impl Pod for u64 {}
+unsafe impl Pod for i64 {}
+unsafe impl Pod for f32 {}
+unsafe impl Pod for f64 {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+// Enum that represents how often buffer data is expected to change, a hint for where the
+// driver places the buffer
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Usage {
+    // Uploaded once and drawn many times, e.g. meshes
+    StaticDraw,
+    // Updated now and then and drawn many times, e.g. per-frame uniforms
+    DynamicDraw,
+    // Updated before nearly every draw, e.g. streamed particles or debug lines
+    StreamDraw,
+    // Written by the gpu and read back once, e.g. pixel readbacks
+    StreamRead,
+}
+
+// Implementation of usage
+impl Usage {
+    // Function to get the matching gl enum value
+    pub fn to_gl(self) -> gl::types::GLenum {
+        match self {
+            Usage::StaticDraw => gl::STATIC_DRAW,
+            Usage::DynamicDraw => gl::DYNAMIC_DRAW,
+            Usage::StreamDraw => gl::STREAM_DRAW,
+            Usage::StreamRead => gl::STREAM_READ,
+        }
+    }
+}
+
+// Struct that represents access flags of a mapped buffer range, combined with |
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MapAccess(gl::types::GLbitfield);
+
+// Implementation of map access
+impl MapAccess {
+    // The range is read by the cpu
+    pub const READ: MapAccess = MapAccess(gl::MAP_READ_BIT);
+    // The range is written by the cpu
+    pub const WRITE: MapAccess = MapAccess(gl::MAP_WRITE_BIT);
+    // Previous contents of the range are discarded, they are undefined in the mapped slice
+    pub const INVALIDATE_RANGE: MapAccess = MapAccess(gl::MAP_INVALIDATE_RANGE_BIT);
+    // Previous contents of the whole buffer are discarded
+    pub const INVALIDATE_BUFFER: MapAccess = MapAccess(gl::MAP_INVALIDATE_BUFFER_BIT);
+    // The driver does not wait for pending draws using the buffer, the caller has to make
+    // sure they are not using the mapped range (e.g. with a fence or a ring of ranges)
+    pub const UNSYNCHRONIZED: MapAccess = MapAccess(gl::MAP_UNSYNCHRONIZED_BIT);
+    // The range stays mapped while the gpu uses the buffer, storage has to be allocated with
+    // allocate_storage and the same flag
+    pub const PERSISTENT: MapAccess = MapAccess(gl::MAP_PERSISTENT_BIT);
+    // Writes become visible to the gpu without explicit flushes or barriers
+    pub const COHERENT: MapAccess = MapAccess(gl::MAP_COHERENT_BIT);
+
+    // Function to get the gl bitfield value
+    pub fn to_gl(self) -> gl::types::GLbitfield {
+        self.0
+    }
+}
+
+// Implement | for map access to combine flags
+impl ::std::ops::BitOr for MapAccess {
+    type Output = MapAccess;
+
+    fn bitor(self, other: MapAccess) -> MapAccess {
+        MapAccess(self.0 | other.0)
+    }
+}
+
+// Struct that represents a mapped range of a buffer as a slice of possibly uninitialized
+// elements (write-only or invalidated ranges have undefined contents), the range is unmapped
+// when the guard is dropped
+pub struct MappedRange<'a, B, T>
+where
+    B: BufferType,
+{
+    // The mapped buffer, borrowed so it outlives the mapping
+    buffer: &'a Buffer<B>,
+    // The mapped memory
+    data: &'a mut [MaybeUninit<T>],
+}
+
+// Implementation of mapped range
+impl<'a, B, T> MappedRange<'a, B, T>
+where
+    B: BufferType,
+    T: Copy,
+{
+    // Function to write values into the range starting at element index
+    pub fn write(&mut self, index: usize, values: &[T]) {
+        let elements = &mut self.data[index..index + values.len()];
+        for (element, &value) in elements.iter_mut().zip(values) {
+            element.write(value);
+        }
+    }
+}
+
+// Implement deref trait for the mapped range struct, reading elements is only sound if the
+// range was mapped with READ and its previous contents were not invalidated
+impl<'a, B, T> ::std::ops::Deref for MappedRange<'a, B, T>
+where
+    B: BufferType,
+{
+    type Target = [MaybeUninit<T>];
+
+    fn deref(&self) -> &[MaybeUninit<T>] {
+        self.data
+    }
+}
+
+// Implement deref mut trait for the mapped range struct
+impl<'a, B, T> ::std::ops::DerefMut for MappedRange<'a, B, T>
+where
+    B: BufferType,
+{
+    fn deref_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        self.data
+    }
+}
+
+// Implement drop trait for the mapped range struct
+impl<'a, B, T> Drop for MappedRange<'a, B, T>
+where
+    B: BufferType,
+{
+    fn drop(&mut self) {
+        self.buffer.bind();
+        unsafe {
+            // Returns false if the contents were lost while mapped (e.g. on a mode switch),
+            // the next upload replaces them anyway
+            self.buffer.gl.UnmapBuffer(B::BUFFER_TYPE);
+        }
+        self.buffer.unbind();
+    }
+}
+
 // Struct that represents a array buffer
 pub struct Buffer<B>
 where
@@ -52,14 +232,157 @@ where
         }
     }
 
-    // Function to draw the data
-    pub fn static_draw_data<T>(&self, data: &[T]) {
+    // Function to bind the whole buffer to an indexed binding point, for uniform and
+    // shader storage buffers
+    pub fn bind_base(&self, binding: u32) {
+        unsafe {
+            self.gl.BindBufferBase(B::BUFFER_TYPE, binding, self.vbo);
+        }
+    }
+
+    // Function to bind a byte range of the buffer to an indexed binding point, offset has
+    // to respect the offset alignment of the binding type
+    pub fn bind_range(&self, binding: u32, offset: usize, size: usize) {
+        unsafe {
+            self.gl.BindBufferRange(
+                B::BUFFER_TYPE,
+                binding,
+                self.vbo,
+                offset as gl::types::GLintptr,
+                size as gl::types::GLsizeiptr,
+            );
+        }
+    }
+
+    // Function to allocate size bytes of uninitialized storage in the bound buffer, e.g.
+    // once for geometry which is then updated with update_sub_data every frame
+    pub fn allocate(&self, size: usize, usage: Usage) {
         unsafe {
             self.gl.BufferData(
-                gl::ARRAY_BUFFER,                                                   // target
+                B::BUFFER_TYPE,                // target
+                size as gl::types::GLsizeiptr, // size of data in bytes
+                ::std::ptr::null(),            // no data, storage only
+                usage.to_gl(),                 // usage
+            );
+        }
+    }
+
+    // Function to allocate size bytes of immutable storage in the bound buffer, which can't
+    // be resized but can stay mapped with the PERSISTENT flag (the flags given here limit
+    // the access of later mappings)
+    pub fn allocate_storage(&self, size: usize, flags: MapAccess) {
+        unsafe {
+            self.gl.BufferStorage(
+                B::BUFFER_TYPE,                // target
+                size as gl::types::GLsizeiptr, // size of data in bytes
+                ::std::ptr::null(),            // no data, storage only
+                flags.to_gl(),                 // storage flags
+            );
+        }
+    }
+
+    // Function to overwrite data starting at byte offset in the bound buffer without
+    // reallocating its storage, the range has to fit into the allocated size
+    pub fn update_sub_data<T: BufferData<B>>(&self, offset: usize, data: &[T]) {
+        unsafe {
+            self.gl.BufferSubData(
+                B::BUFFER_TYPE,                                         // target
+                offset as gl::types::GLintptr,                          // offset in bytes
+                ::std::mem::size_of_val(data) as gl::types::GLsizeiptr, // size of data in bytes
+                data.as_ptr() as *const gl::types::GLvoid,              // pointer to data
+            );
+        }
+    }
+
+    // Function to map count elements starting at byte offset into cpu memory, e.g. to write
+    // streamed vertices straight into the buffer. Fails if the offset is not aligned for T,
+    // the range does not fit into the allocated storage or the buffer is already mapped.
+    pub fn map_range<T: BufferData<B> + Copy>(
+        &self,
+        offset: usize,
+        count: usize,
+        access: MapAccess,
+    ) -> Result<MappedRange<'_, B, T>, Error> {
+        // Mappings start at 64 byte aligned addresses (GL_MIN_MAP_BUFFER_ALIGNMENT), so only
+        // the offset can misalign T
+        let align = ::std::mem::align_of::<T>();
+        if offset & (align - 1) != 0 {
+            return Err(Error::MisalignedOffset { offset, align });
+        }
+
+        // A wrapped size would map less memory than the returned slice covers
+        let size = count
+            .checked_mul(::std::mem::size_of::<T>())
+            .ok_or(Error::MapFailed {
+                offset,
+                size: usize::MAX,
+            })?;
+        self.bind();
+        let pointer = unsafe {
+            self.gl.MapBufferRange(
+                B::BUFFER_TYPE,                // target
+                offset as gl::types::GLintptr, // offset in bytes
+                size as gl::types::GLsizeiptr, // size of data in bytes
+                access.to_gl(),                // access flags
+            )
+        };
+        self.unbind();
+
+        if pointer.is_null() {
+            return Err(Error::MapFailed { offset, size });
+        }
+
+        Ok(MappedRange {
+            buffer: self,
+            data: unsafe {
+                ::std::slice::from_raw_parts_mut(pointer as *mut MaybeUninit<T>, count)
+            },
+        })
+    }
+
+    // Function to read back data starting at byte offset in the bound buffer into the slice,
+    // waits for the gpu to finish writing the buffer (e.g. results of a compute dispatch).
+    // T has to be plain old data since the gpu may have written any bytes.
+    pub fn get_sub_data<T: BufferData<B> + Pod>(&self, offset: usize, data: &mut [T]) {
+        unsafe {
+            self.gl.GetBufferSubData(
+                B::BUFFER_TYPE,                                         // target
+                offset as gl::types::GLintptr,                          // offset in bytes
+                ::std::mem::size_of_val(data) as gl::types::GLsizeiptr, // size of data in bytes
+                data.as_mut_ptr() as *mut gl::types::GLvoid,            // pointer to data
+            );
+        }
+    }
+
+    // Function to get the buffer id
+    pub fn id(&self) -> gl::types::GLuint {
+        self.vbo
+    }
+
+    // Function to upload data which is drawn many times without changing
+    pub fn static_draw_data<T: BufferData<B>>(&self, data: &[T]) {
+        self.buffer_data(data, Usage::StaticDraw);
+    }
+
+    // Function to upload data which changes now and then
+    pub fn dynamic_draw_data<T: BufferData<B>>(&self, data: &[T]) {
+        self.buffer_data(data, Usage::DynamicDraw);
+    }
+
+    // Function to upload data which changes about every time it is drawn
+    pub fn stream_draw_data<T: BufferData<B>>(&self, data: &[T]) {
+        self.buffer_data(data, Usage::StreamDraw);
+    }
+
+    // Function to upload data into the bound buffer with the given usage hint, replacing
+    // the previous storage
+    pub fn buffer_data<T: BufferData<B>>(&self, data: &[T], usage: Usage) {
+        unsafe {
+            self.gl.BufferData(
+                B::BUFFER_TYPE,                                                     // target
                 (data.len() * ::std::mem::size_of::<T>()) as gl::types::GLsizeiptr, // size of data in bytes
                 data.as_ptr() as *const gl::types::GLvoid, // pointer to data
-                gl::STATIC_DRAW,                           // usage
+                usage.to_gl(),                             // usage
             );
         }
     }
@@ -111,6 +434,49 @@ impl VertexArray {
             self.gl.BindVertexArray(0);
         }
     }
+
+    // Function to describe the attributes of V (from its derived layout) as read from a
+    // buffer binding point instead of from a specific buffer, so the same vertex array can
+    // draw from many buffers switched with bind_vertex_buffer. Divisor applies to the whole
+    // binding, 0 advances per vertex and N every N instances. The vertex array must be bound.
+    pub fn set_format<V: Vertex>(&self, binding: u32, divisor: u32) {
+        unsafe {
+            for attribute in V::layout() {
+                self.gl.EnableVertexAttribArray(attribute.location);
+                if attribute.integer {
+                    self.gl.VertexAttribIFormat(
+                        attribute.location,
+                        attribute.components,
+                        attribute.gl_type,
+                        attribute.offset as gl::types::GLuint, // offset within the vertex
+                    );
+                } else {
+                    self.gl.VertexAttribFormat(
+                        attribute.location,
+                        attribute.components,
+                        attribute.gl_type,
+                        attribute.normalized as gl::types::GLboolean,
+                        attribute.offset as gl::types::GLuint, // offset within the vertex
+                    );
+                }
+                self.gl.VertexAttribBinding(attribute.location, binding);
+            }
+            self.gl.VertexBindingDivisor(binding, divisor);
+        }
+    }
+
+    // Function to attach a buffer of V vertices starting at byte offset to a binding point
+    // configured with set_format. The vertex array must be bound.
+    pub fn bind_vertex_buffer<V: Vertex>(&self, binding: u32, buffer: &ArrayBuffer, offset: usize) {
+        unsafe {
+            self.gl.BindVertexBuffer(
+                binding,
+                buffer.id(),
+                offset as gl::types::GLintptr, // offset in bytes
+                ::std::mem::size_of::<V>() as gl::types::GLsizei, // stride
+            );
+        }
+    }
 }
 
 // Implement drop trait for the vertex array struct
@@ -138,6 +504,253 @@ impl BufferType for BufferTypeElementArray {
     const BUFFER_TYPE: gl::types::GLuint = gl::ELEMENT_ARRAY_BUFFER;
 }
 
+// Struct that represents a buffer type uniform
+pub struct BufferTypeUniform;
+
+// Implement the buffer type trait for the buffer type uniform struct
+impl BufferType for BufferTypeUniform {
+    const BUFFER_TYPE: gl::types::GLuint = gl::UNIFORM_BUFFER;
+}
+
+// Struct that represents a buffer type shader storage
+pub struct BufferTypeShaderStorage;
+
+// Implement the buffer type trait for the buffer type shader storage struct
+impl BufferType for BufferTypeShaderStorage {
+    const BUFFER_TYPE: gl::types::GLuint = gl::SHADER_STORAGE_BUFFER;
+}
+
+// Struct that represents a buffer type pixel pack
+pub struct BufferTypePixelPack;
+
+// Implement the buffer type trait for the buffer type pixel pack struct
+impl BufferType for BufferTypePixelPack {
+    const BUFFER_TYPE: gl::types::GLuint = gl::PIXEL_PACK_BUFFER;
+}
+
 // Public type aliases
 pub type ArrayBuffer = Buffer<BufferTypeArray>;
 pub type ElementArrayBuffer = Buffer<BufferTypeElementArray>;
+pub type UniformBuffer = Buffer<BufferTypeUniform>;
+pub type ShaderStorageBuffer = Buffer<BufferTypeShaderStorage>;
+pub type PixelPackBuffer = Buffer<BufferTypePixelPack>;
+
+// Struct that assigns block names to binding points, so a block shared by many programs
+// (e.g. camera matrices) always uses the same point and one buffer feeds all of them
+pub struct BlockBindings<B>
+where
+    B: BufferType,
+{
+    // Binding point of each block name
+    points: HashMap<String, u32>,
+    // Next free binding point
+    next: u32,
+    // The PhantomData marker
+    _marker: ::std::marker::PhantomData<B>,
+}
+
+// Implement the block bindings struct
+impl<B> BlockBindings<B>
+where
+    B: BufferType,
+{
+    // Constructor which hands out binding points starting at first, points below it can be
+    // used directly (e.g. per_draw::PER_DRAW_BINDING)
+    pub fn new(first: u32) -> Self {
+        Self {
+            points: HashMap::new(),
+            next: first,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    // Function to get binding point of a block, assigning the next free one on first use
+    pub fn binding(&mut self, block_name: &str) -> u32 {
+        if let Some(&binding) = self.points.get(block_name) {
+            return binding;
+        }
+
+        let binding = self.next;
+        self.next += 1;
+        self.points.insert(block_name.into(), binding);
+        binding
+    }
+
+    // Function to get binding point of a block if it was assigned
+    pub fn get(&self, block_name: &str) -> Option<u32> {
+        self.points.get(block_name).copied()
+    }
+
+    // Function to connect every assigned block the program declares to its binding point
+    pub fn apply(&self, program: &Program) {
+        for (block_name, &binding) in self.points.iter() {
+            match B::BUFFER_TYPE {
+                gl::SHADER_STORAGE_BUFFER => program.bind_storage_block(block_name, binding),
+                _ => program.bind_uniform_block(block_name, binding),
+            };
+        }
+    }
+}
+
+// Public type aliases
+pub type UniformBindings = BlockBindings<BufferTypeUniform>;
+pub type StorageBindings = BlockBindings<BufferTypeShaderStorage>;
+
+// Trait to represent the type of a single index in an index buffer
+pub trait IndexType {
+    const INDEX_TYPE: gl::types::GLenum;
+}
+
+// Implement the index type trait for unsigned integer types supported by gl
+impl IndexType for u8 {
+    const INDEX_TYPE: gl::types::GLenum = gl::UNSIGNED_BYTE;
+}
+
+impl IndexType for u16 {
+    const INDEX_TYPE: gl::types::GLenum = gl::UNSIGNED_SHORT;
+}
+
+impl IndexType for u32 {
+    const INDEX_TYPE: gl::types::GLenum = gl::UNSIGNED_INT;
+}
+
+// Struct that represents an element array buffer which remembers its index count and type
+pub struct IndexBuffer<T>
+where
+    T: IndexType,
+{
+    // The underlying element array buffer
+    buffer: ElementArrayBuffer,
+    // The number of indices uploaded
+    count: usize,
+    // The PhantomData marker
+    _marker: ::std::marker::PhantomData<T>,
+}
+
+// Implement the index buffer struct
+impl<T> IndexBuffer<T>
+where
+    T: IndexType,
+{
+    // Constructor for an empty index buffer
+    pub fn new(gl: &gl::Gl) -> Self {
+        Self {
+            buffer: ElementArrayBuffer::new(gl),
+            count: 0,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    // Constructor for an index buffer filled with indices
+    pub fn from_indices(gl: &gl::Gl, indices: &[T]) -> Self {
+        let mut index_buffer = Self::new(gl);
+        index_buffer.bind();
+        index_buffer.static_draw_data(indices);
+        index_buffer.unbind();
+        index_buffer
+    }
+
+    // Function to bind the index buffer (binding is recorded by the currently bound vertex array)
+    pub fn bind(&self) {
+        self.buffer.bind();
+    }
+
+    // Function to unbind the index buffer
+    pub fn unbind(&self) {
+        self.buffer.unbind();
+    }
+
+    // Function to upload indices and remember their count
+    pub fn static_draw_data(&mut self, indices: &[T]) {
+        self.buffer.static_draw_data(indices);
+        self.count = indices.len();
+    }
+
+    // Function to get the number of indices
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Function to get the gl type of the indices
+    pub fn index_type(&self) -> gl::types::GLenum {
+        T::INDEX_TYPE
+    }
+
+    // Function to draw all indices, vertex array referencing this buffer must be bound
+    pub fn draw_elements(&self, mode: gl::types::GLenum) {
+        draw_elements(&self.buffer.gl, mode, self.count, T::INDEX_TYPE, 0);
+    }
+
+    // Function to draw all indices the given number of times, vertex array referencing this
+    // buffer must be bound
+    pub fn draw_elements_instanced(&self, mode: gl::types::GLenum, instances: usize) {
+        draw_elements_instanced(
+            &self.buffer.gl,
+            mode,
+            self.count,
+            T::INDEX_TYPE,
+            0,
+            instances,
+        );
+    }
+}
+
+// Function to issue an indexed draw call from the currently bound element array buffer
+pub fn draw_elements(
+    gl: &gl::Gl,
+    mode: gl::types::GLenum,
+    count: usize,
+    index_type: gl::types::GLenum,
+    offset: usize,
+) {
+    validate_before_draw(gl);
+    unsafe {
+        gl.DrawElements(
+            mode,                               // mode
+            count as gl::types::GLsizei,        // number of indices to be rendered
+            index_type,                         // type of the indices
+            offset as *const gl::types::GLvoid, // byte offset into the element array buffer
+        );
+    }
+}
+
+// Function to issue an instanced indexed draw call from the currently bound element array
+// buffer, attributes with a divisor advance once per instance instead of once per vertex
+pub fn draw_elements_instanced(
+    gl: &gl::Gl,
+    mode: gl::types::GLenum,
+    count: usize,
+    index_type: gl::types::GLenum,
+    offset: usize,
+    instances: usize,
+) {
+    validate_before_draw(gl);
+    unsafe {
+        gl.DrawElementsInstanced(
+            mode,                               // mode
+            count as gl::types::GLsizei,        // number of indices to be rendered
+            index_type,                         // type of the indices
+            offset as *const gl::types::GLvoid, // byte offset into the element array buffer
+            instances as gl::types::GLsizei,    // number of instances
+        );
+    }
+}
+
+// Function to issue an instanced draw call from the currently bound vertex array
+pub fn draw_arrays_instanced(
+    gl: &gl::Gl,
+    mode: gl::types::GLenum,
+    first: usize,
+    count: usize,
+    instances: usize,
+) {
+    validate_before_draw(gl);
+    unsafe {
+        gl.DrawArraysInstanced(
+            mode,                            // mode
+            first as gl::types::GLint,       // starting index in the enabled arrays
+            count as gl::types::GLsizei,     // number of vertices to be rendered
+            instances as gl::types::GLsizei, // number of instances
+        );
+    }
+}
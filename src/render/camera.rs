@@ -0,0 +1,152 @@
+// Import dependencies
+use crate::math::{Mat4, Vec3, Vec4};
+use crate::render::Program;
+
+// Names of the uniforms the camera writes to
+const VIEW_UNIFORM: &str = "View";
+const PROJECTION_UNIFORM: &str = "Projection";
+
+// Name of the uniform block the camera data is shared through, shaders declare it as
+// `layout (std140) uniform Camera { mat4 View; mat4 Projection; vec4 Position; };`
+pub const CAMERA_BLOCK: &str = "Camera";
+
+// Struct that represents camera data laid out to match the std140 Camera block
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct CameraBlock {
+    pub view: Mat4,
+    pub projection: Mat4,
+    // Camera position in world space, w is unused
+    pub position: Vec4,
+}
+
+// Enum that represents a camera projection
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Projection {
+    // Perspective projection with vertical field of view in radians
+    Perspective { fov_y: f32, near: f32, far: f32 },
+    // Orthographic projection with visible height in world units
+    Orthographic { height: f32, near: f32, far: f32 },
+}
+
+// Struct that represents a camera looking from position at target
+#[derive(Copy, Clone, Debug)]
+pub struct Camera {
+    position: Vec3,
+    target: Vec3,
+    up: Vec3,
+    projection: Projection,
+    // Width divided by height of the viewport
+    aspect: f32,
+}
+
+// Implementation of camera
+impl Camera {
+    // Function to create a perspective camera at (0, 0, 1) looking at the origin
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Camera {
+        Camera::new(Projection::Perspective { fov_y, near, far }, aspect)
+    }
+
+    // Function to create an orthographic camera at (0, 0, 1) looking at the origin
+    pub fn orthographic(height: f32, aspect: f32, near: f32, far: f32) -> Camera {
+        Camera::new(Projection::Orthographic { height, near, far }, aspect)
+    }
+
+    // Function to create camera with the given projection
+    pub fn new(projection: Projection, aspect: f32) -> Camera {
+        Camera {
+            position: Vec3::Z,
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            projection,
+            aspect,
+        }
+    }
+
+    // Function to place the camera
+    pub fn look_at(&mut self, position: Vec3, target: Vec3, up: Vec3) {
+        self.position = position;
+        self.target = target;
+        self.up = up;
+    }
+
+    // Function to update aspect ratio after viewport resize
+    pub fn resize(&mut self, width: i32, height: i32) {
+        if width > 0 && height > 0 {
+            self.aspect = width as f32 / height as f32;
+        }
+    }
+
+    // Function to replace the projection
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    // Function to get the projection
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    // Function to get the camera position
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    // Function to get the point the camera looks at
+    pub fn target(&self) -> Vec3 {
+        self.target
+    }
+
+    // Function to get the up direction
+    pub fn up(&self) -> Vec3 {
+        self.up
+    }
+
+    // Function to get the aspect ratio
+    pub fn aspect(&self) -> f32 {
+        self.aspect
+    }
+
+    // Function to compute the view matrix
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at_rh(self.position, self.target, self.up)
+    }
+
+    // Function to compute the projection matrix
+    pub fn projection_matrix(&self) -> Mat4 {
+        match self.projection {
+            Projection::Perspective { fov_y, near, far } => {
+                Mat4::perspective_rh_gl(fov_y, self.aspect, near, far)
+            }
+            Projection::Orthographic { height, near, far } => {
+                let half_width = height * self.aspect / 2.0;
+                let half_height = height / 2.0;
+                Mat4::orthographic_rh_gl(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    near,
+                    far,
+                )
+            }
+        }
+    }
+
+    // Function to get data for the Camera uniform block, uploaded once per frame into a
+    // uniform buffer shared by all programs instead of setting uniforms per program
+    pub fn block(&self) -> CameraBlock {
+        CameraBlock {
+            view: self.view_matrix(),
+            projection: self.projection_matrix(),
+            position: self.position.extend(1.0),
+        }
+    }
+
+    // Function to upload view and projection matrices to "View" and "Projection" uniforms
+    // (program must be in use)
+    pub fn upload_uniforms(&self, program: &Program) {
+        program.set_uniform_mat4(VIEW_UNIFORM, &self.view_matrix());
+        program.set_uniform_mat4(PROJECTION_UNIFORM, &self.projection_matrix());
+    }
+}
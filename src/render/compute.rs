@@ -0,0 +1,160 @@
+// Import dependencies
+use gl;
+
+use crate::render::buffer::ShaderStorageBuffer;
+use crate::render::texture::Texture2D;
+use crate::render::{Error, Program, Shader};
+use crate::resources::Resources;
+
+// Enum that represents how a compute shader accesses a bound image
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImageAccess {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+// Implementation of image access
+impl ImageAccess {
+    // Function to get the matching gl enum value
+    pub fn to_gl(self) -> gl::types::GLenum {
+        match self {
+            ImageAccess::ReadOnly => gl::READ_ONLY,
+            ImageAccess::WriteOnly => gl::WRITE_ONLY,
+            ImageAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
+}
+
+// Struct that represents a program made of a single compute shader
+pub struct ComputeProgram {
+    // The gl context
+    gl: gl::Gl,
+    // The linked program
+    program: Program,
+    // Local work group size declared by the shader
+    work_group_size: [u32; 3],
+}
+
+// Implementation of compute program
+impl ComputeProgram {
+    // Function to create compute program from resource, e.g. "shaders/blur" loads
+    // shaders/blur.comp
+    pub fn from_res(gl: &gl::Gl, res: &Resources, name: &str) -> Result<ComputeProgram, Error> {
+        let shader = Shader::from_res(gl, res, &format!("{}.comp", name))?;
+
+        let program = Program::from_shaders(gl, &[shader]).map_err(|message| Error::LinkError {
+            name: name.into(),
+            message,
+        })?;
+
+        Ok(ComputeProgram::from_program(gl, program))
+    }
+
+    // Function to wrap a linked program containing a compute shader
+    pub fn from_program(gl: &gl::Gl, program: Program) -> ComputeProgram {
+        let mut work_group_size: [gl::types::GLint; 3] = [0; 3];
+        unsafe {
+            gl.GetProgramiv(
+                program.id(),
+                gl::COMPUTE_WORK_GROUP_SIZE,
+                work_group_size.as_mut_ptr(),
+            );
+        }
+
+        ComputeProgram {
+            gl: gl.clone(),
+            program,
+            work_group_size: work_group_size.map(|size| size as u32),
+        }
+    }
+
+    // Function to get the underlying program, e.g. to set uniforms
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    // Function to get the local work group size declared by the shader
+    pub fn work_group_size(&self) -> [u32; 3] {
+        self.work_group_size
+    }
+
+    // Function to set program as used
+    pub fn set_used(&self) {
+        self.program.set_used();
+    }
+
+    // Function to run the given number of work groups (program must be in use)
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
+        unsafe {
+            self.gl.DispatchCompute(x, y, z);
+        }
+    }
+
+    // Function to run enough work groups to cover the given number of invocations in each
+    // dimension, e.g. one invocation per pixel of an image (program must be in use)
+    pub fn dispatch_for(&self, width: u32, height: u32, depth: u32) {
+        let [x, y, z] = self.work_group_size;
+        self.dispatch(
+            width.div_ceil(x.max(1)),
+            height.div_ceil(y.max(1)),
+            depth.div_ceil(z.max(1)),
+        );
+    }
+
+    // Function to bind a shader storage buffer to a `layout (binding = N) buffer` block
+    pub fn bind_storage_buffer(&self, binding: u32, buffer: &ShaderStorageBuffer) {
+        buffer.bind_base(binding);
+    }
+
+    // Function to bind base level of a texture to a `layout (binding = N) image2D` uniform,
+    // the shader declared format has to match the texture format
+    pub fn bind_image(&self, unit: u32, texture: &Texture2D, access: ImageAccess) {
+        unsafe {
+            self.gl.BindImageTexture(
+                unit,                     // image unit
+                texture.id(),             // texture
+                0,                        // level
+                gl::FALSE,                // layered
+                0,                        // layer
+                access.to_gl(),           // access
+                texture.format().to_gl(), // format
+            );
+        }
+    }
+}
+
+// Function to order memory accesses of earlier shader invocations before the later uses
+// given by barriers, e.g. gl::SHADER_STORAGE_BARRIER_BIT to read results of a dispatch in
+// the next one
+pub fn memory_barrier(gl: &gl::Gl, barriers: gl::types::GLbitfield) {
+    unsafe {
+        gl.MemoryBarrier(barriers);
+    }
+}
+
+// Function to make shader storage writes visible to later shader reads
+pub fn storage_barrier(gl: &gl::Gl) {
+    memory_barrier(gl, gl::SHADER_STORAGE_BARRIER_BIT);
+}
+
+// Function to make image writes visible to later image loads and stores
+pub fn image_barrier(gl: &gl::Gl) {
+    memory_barrier(gl, gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+}
+
+// Function to make image writes visible to later texture sampling
+pub fn texture_fetch_barrier(gl: &gl::Gl) {
+    memory_barrier(gl, gl::TEXTURE_FETCH_BARRIER_BIT);
+}
+
+// Function to make shader storage writes visible to vertex fetches and draw commands which
+// use the buffer as vertex, index or indirect data
+pub fn vertex_barrier(gl: &gl::Gl) {
+    memory_barrier(
+        gl,
+        gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT
+            | gl::ELEMENT_ARRAY_BARRIER_BIT
+            | gl::COMMAND_BARRIER_BIT,
+    );
+}
@@ -2,6 +2,71 @@
 // Import dependencies
 use gl;
 
+// Struct that describes one vertex attribute, #[derive(VertexAttribPointers)] generates a
+// `LAYOUT` list of these for the whole vertex, e.g. to check it against the inputs of a
+// shader or to set up vertex arrays some other way
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AttributeDesc {
+    // Attribute location ("layout (location = N)")
+    pub location: u32,
+    // Number of components, 1 to 4
+    pub components: i32,
+    // Component data type, e.g. gl::FLOAT
+    pub gl_type: gl::types::GLenum,
+    // Integer components are converted to floats in [0, 1] or [-1, 1]
+    pub normalized: bool,
+    // Integer components stay integers in the shader (glVertexAttribIPointer)
+    pub integer: bool,
+    // Byte offset from the start of the vertex
+    pub offset: usize,
+}
+
+// Implementation of attribute desc
+impl AttributeDesc {
+    // Function to create description of a float attribute at location 0 and offset 0, the
+    // way vertex data types describe themselves
+    pub const fn float(components: i32, gl_type: gl::types::GLenum, normalized: bool) -> Self {
+        AttributeDesc {
+            location: 0,
+            components,
+            gl_type,
+            normalized,
+            integer: false,
+            offset: 0,
+        }
+    }
+
+    // Function to create description of an integer attribute at location 0 and offset 0
+    pub const fn integer(components: i32, gl_type: gl::types::GLenum) -> Self {
+        AttributeDesc {
+            location: 0,
+            components,
+            gl_type,
+            normalized: false,
+            integer: true,
+            offset: 0,
+        }
+    }
+}
+
+// Trait to represent a vertex data type, #[derive(VertexAttribPointers)] numbers the
+// attribute locations of the following fields with it
+pub trait VertexAttrib {
+    // Number of consecutive attribute locations the type takes
+    const LOCATIONS: usize = 1;
+}
+
+impl VertexAttrib for VertVec3D {}
+impl VertexAttrib for VertVec2D {}
+impl VertexAttrib for VertVec4D {}
+impl VertexAttrib for VertRGBA {}
+impl VertexAttrib for VertU8U8U8U8Float {}
+impl VertexAttrib for VertI8 {}
+impl VertexAttrib for VertI8Float {}
+impl VertexAttrib for VertF16 {}
+impl VertexAttrib for VertVec2F16 {}
+impl VertexAttrib for VertVec4F16 {}
+
 // Struct that represents a vertex with a position and a color
 #[derive(Copy, Clone, Debug)]
 #[repr(C, packed)]
@@ -13,6 +78,9 @@ pub struct VertVec3D {
 
 // Implement a constructor for the vertex struct
 impl VertVec3D {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::float(3, gl::FLOAT, false);
+
     // Function which creates a new vertex
     pub fn new(x: f32, y: f32, z: f32) -> Self {
         Self { x, y, z }
@@ -45,6 +113,98 @@ impl From<(f32, f32, f32)> for VertVec3D {
     }
 }
 
+// Struct that represents a 2 dimensional float vector, e.g. texture coordinates
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct VertVec2D {
+    pub x: f32,
+    pub y: f32,
+}
+
+// Implement a constructor for the vertex struct
+impl VertVec2D {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::float(2, gl::FLOAT, false);
+
+    // Function which creates a new vertex
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    // Function which enables and sets the vertex attribute pointers
+    pub unsafe fn vertex_attrib_pointer(
+        gl: &gl::Gl,
+        stride: usize,
+        location: usize,
+        offset: usize,
+    ) {
+        // Enable the vertex attribute array at the given location
+        gl.EnableVertexAttribArray(location as gl::types::GLuint);
+        gl.VertexAttribPointer(
+            location as gl::types::GLuint,
+            2,         // the number of components per generic vertex attribute
+            gl::FLOAT, // data type
+            gl::FALSE, // normalized (int-to-float conversion)
+            stride as gl::types::GLint,
+            offset as *const gl::types::GLvoid,
+        );
+    }
+}
+
+// Implement a constructor for the vertex struct
+impl From<(f32, f32)> for VertVec2D {
+    fn from(tuple: (f32, f32)) -> Self {
+        Self::new(tuple.0, tuple.1)
+    }
+}
+
+// Struct that represents a 4 dimensional float vector, e.g. full precision colors
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct VertVec4D {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+// Implement a constructor for the vertex struct
+impl VertVec4D {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::float(4, gl::FLOAT, false);
+
+    // Function which creates a new vertex
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    // Function which enables and sets the vertex attribute pointers
+    pub unsafe fn vertex_attrib_pointer(
+        gl: &gl::Gl,
+        stride: usize,
+        location: usize,
+        offset: usize,
+    ) {
+        // Enable the vertex attribute array at the given location
+        gl.EnableVertexAttribArray(location as gl::types::GLuint);
+        gl.VertexAttribPointer(
+            location as gl::types::GLuint,
+            4,         // the number of components per generic vertex attribute
+            gl::FLOAT, // data type
+            gl::FALSE, // normalized (int-to-float conversion)
+            stride as gl::types::GLint,
+            offset as *const gl::types::GLvoid,
+        );
+    }
+}
+
+// Implement a constructor for the vertex struct
+impl From<(f32, f32, f32, f32)> for VertVec4D {
+    fn from(tuple: (f32, f32, f32, f32)) -> Self {
+        Self::new(tuple.0, tuple.1, tuple.2, tuple.3)
+    }
+}
+
 // Struct that represents a 4 demensional vector with 2 bits for first and 10 bits for the rest
 #[derive(Copy, Clone, Debug)]
 #[repr(C, packed)]
@@ -63,6 +223,10 @@ impl From<(f32, f32, f32, f32)> for VertRGBA {
 
 // Implement the vertex-rgba struct
 impl VertRGBA {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc =
+        AttributeDesc::float(4, gl::UNSIGNED_INT_2_10_10_10_REV, true);
+
     pub unsafe fn vertex_attrib_pointer(
         gl: &gl::Gl,
         stride: usize,
@@ -82,6 +246,61 @@ impl VertRGBA {
     }
 }
 
+// Struct that represents 4 u8 components normalized to floats in [0, 1], e.g. vertex colors
+// stored in 4 bytes
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct VertU8U8U8U8Float {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+// Implement a constructor for the vertex struct
+impl VertU8U8U8U8Float {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::float(4, gl::UNSIGNED_BYTE, true);
+
+    // Function which creates a new vertex
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    // Function which enables and sets the vertex attribute pointers
+    pub unsafe fn vertex_attrib_pointer(
+        gl: &gl::Gl,
+        stride: usize,
+        location: usize,
+        offset: usize,
+    ) {
+        // Enable the vertex attribute array at the given location
+        gl.EnableVertexAttribArray(location as gl::types::GLuint);
+        gl.VertexAttribPointer(
+            location as gl::types::GLuint,
+            4,                 // the number of components per generic vertex attribute
+            gl::UNSIGNED_BYTE, // data type
+            gl::TRUE,          // normalized (int-to-float conversion)
+            stride as gl::types::GLint,
+            offset as *const gl::types::GLvoid,
+        );
+    }
+}
+
+// Implement a constructor for the vertex struct
+impl From<(u8, u8, u8, u8)> for VertU8U8U8U8Float {
+    fn from(tuple: (u8, u8, u8, u8)) -> Self {
+        Self::new(tuple.0, tuple.1, tuple.2, tuple.3)
+    }
+}
+
+// Implement a constructor for the vertex struct
+impl From<[u8; 4]> for VertU8U8U8U8Float {
+    fn from(array: [u8; 4]) -> Self {
+        Self::new(array[0], array[1], array[2], array[3])
+    }
+}
+
 // Struct that represents i8 vertex
 #[derive(Copy, Clone, Debug)]
 #[repr(C, packed)]
@@ -91,6 +310,9 @@ pub struct VertI8 {
 
 // Implement the vertex-i8 struct
 impl VertI8 {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::integer(1, gl::BYTE);
+
     // Function which creates a new vertex
     pub fn new(x: i8) -> Self {
         Self { x }
@@ -129,6 +351,9 @@ pub struct VertI8Float {
 }
 
 impl VertI8Float {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::float(1, gl::BYTE, true);
+
     // Function which creates a new vertex
     pub fn new(x: i8) -> Self {
         Self { x }
@@ -161,3 +386,465 @@ impl From<i8> for VertI8Float {
         VertI8Float::new(other)
     }
 }
+
+// Struct that represents a 3x3 float matrix stored as 3 columns, it takes 3 consecutive
+// attribute locations (one per column), e.g. instanced normal matrices
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct VertMat3 {
+    pub columns: [[f32; 3]; 3],
+}
+
+impl VertMat3 {
+    // Attribute description of one column used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = VertVec3D::ATTRIBUTE;
+
+    // Function which creates a new matrix from columns
+    pub fn new(columns: [[f32; 3]; 3]) -> Self {
+        Self { columns }
+    }
+
+    // Function which enables and sets the vertex attribute pointers of every column
+    pub unsafe fn vertex_attrib_pointer(
+        gl: &gl::Gl,
+        stride: usize,
+        location: usize,
+        offset: usize,
+    ) {
+        for column in 0..Self::LOCATIONS {
+            VertVec3D::vertex_attrib_pointer(
+                gl,
+                stride,
+                location + column,
+                offset + column * ::std::mem::size_of::<[f32; 3]>(),
+            );
+        }
+    }
+}
+
+// Implement vertex attrib trait for the matrix struct, one location per column
+impl VertexAttrib for VertMat3 {
+    const LOCATIONS: usize = 3;
+}
+
+// Implement a constructor for the matrix struct
+impl From<crate::math::Mat3> for VertMat3 {
+    fn from(other: crate::math::Mat3) -> Self {
+        VertMat3::new(other.to_cols_array_2d())
+    }
+}
+
+// Struct that represents a 4x4 float matrix stored as 4 columns, it takes 4 consecutive
+// attribute locations (one per column), e.g. instanced model matrices
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct VertMat4 {
+    pub columns: [[f32; 4]; 4],
+}
+
+impl VertMat4 {
+    // Attribute description of one column used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = VertVec4D::ATTRIBUTE;
+
+    // Function which creates a new matrix from columns
+    pub fn new(columns: [[f32; 4]; 4]) -> Self {
+        Self { columns }
+    }
+
+    // Function which enables and sets the vertex attribute pointers of every column
+    pub unsafe fn vertex_attrib_pointer(
+        gl: &gl::Gl,
+        stride: usize,
+        location: usize,
+        offset: usize,
+    ) {
+        for column in 0..Self::LOCATIONS {
+            VertVec4D::vertex_attrib_pointer(
+                gl,
+                stride,
+                location + column,
+                offset + column * ::std::mem::size_of::<[f32; 4]>(),
+            );
+        }
+    }
+}
+
+// Implement vertex attrib trait for the matrix struct, one location per column
+impl VertexAttrib for VertMat4 {
+    const LOCATIONS: usize = 4;
+}
+
+// Implement a constructor for the matrix struct
+impl From<crate::math::Mat4> for VertMat4 {
+    fn from(other: crate::math::Mat4) -> Self {
+        VertMat4::new(other.to_cols_array_2d())
+    }
+}
+
+// Struct that represents a half precision float, e.g. a per-vertex weight in 2 bytes
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct VertF16 {
+    // Bits of the half float
+    pub x: u16,
+}
+
+// Implement a constructor for the vertex struct
+impl VertF16 {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::float(1, gl::HALF_FLOAT, false);
+
+    // Function which creates a new vertex, rounding to the nearest half float
+    pub fn new(x: f32) -> Self {
+        Self { x: f32_to_f16(x) }
+    }
+
+    // Function which enables and sets the vertex attribute pointers
+    pub unsafe fn vertex_attrib_pointer(
+        gl: &gl::Gl,
+        stride: usize,
+        location: usize,
+        offset: usize,
+    ) {
+        // Enable the vertex attribute array at the given location
+        gl.EnableVertexAttribArray(location as gl::types::GLuint);
+        gl.VertexAttribPointer(
+            location as gl::types::GLuint,
+            1,              // the number of components per generic vertex attribute
+            gl::HALF_FLOAT, // data type
+            gl::FALSE,      // normalized (int-to-float conversion)
+            stride as gl::types::GLint,
+            offset as *const gl::types::GLvoid,
+        );
+    }
+}
+
+// Implement a constructor for the vertex struct
+impl From<f32> for VertF16 {
+    fn from(other: f32) -> Self {
+        VertF16::new(other)
+    }
+}
+
+// Struct that represents a 2 dimensional half precision vector, e.g. texture coordinates
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct VertVec2F16 {
+    // Bits of the half floats
+    pub x: u16,
+    pub y: u16,
+}
+
+// Implement a constructor for the vertex struct
+impl VertVec2F16 {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::float(2, gl::HALF_FLOAT, false);
+
+    // Function which creates a new vertex, rounding to the nearest half floats
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            x: f32_to_f16(x),
+            y: f32_to_f16(y),
+        }
+    }
+
+    // Function which enables and sets the vertex attribute pointers
+    pub unsafe fn vertex_attrib_pointer(
+        gl: &gl::Gl,
+        stride: usize,
+        location: usize,
+        offset: usize,
+    ) {
+        // Enable the vertex attribute array at the given location
+        gl.EnableVertexAttribArray(location as gl::types::GLuint);
+        gl.VertexAttribPointer(
+            location as gl::types::GLuint,
+            2,              // the number of components per generic vertex attribute
+            gl::HALF_FLOAT, // data type
+            gl::FALSE,      // normalized (int-to-float conversion)
+            stride as gl::types::GLint,
+            offset as *const gl::types::GLvoid,
+        );
+    }
+}
+
+// Implement a constructor for the vertex struct
+impl From<(f32, f32)> for VertVec2F16 {
+    fn from(tuple: (f32, f32)) -> Self {
+        Self::new(tuple.0, tuple.1)
+    }
+}
+
+// Struct that represents a 4 dimensional half precision vector, e.g. HDR colors
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct VertVec4F16 {
+    // Bits of the half floats
+    pub x: u16,
+    pub y: u16,
+    pub z: u16,
+    pub w: u16,
+}
+
+// Implement a constructor for the vertex struct
+impl VertVec4F16 {
+    // Attribute description used by derived vertex layouts
+    pub const ATTRIBUTE: AttributeDesc = AttributeDesc::float(4, gl::HALF_FLOAT, false);
+
+    // Function which creates a new vertex, rounding to the nearest half floats
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self {
+            x: f32_to_f16(x),
+            y: f32_to_f16(y),
+            z: f32_to_f16(z),
+            w: f32_to_f16(w),
+        }
+    }
+
+    // Function which enables and sets the vertex attribute pointers
+    pub unsafe fn vertex_attrib_pointer(
+        gl: &gl::Gl,
+        stride: usize,
+        location: usize,
+        offset: usize,
+    ) {
+        // Enable the vertex attribute array at the given location
+        gl.EnableVertexAttribArray(location as gl::types::GLuint);
+        gl.VertexAttribPointer(
+            location as gl::types::GLuint,
+            4,              // the number of components per generic vertex attribute
+            gl::HALF_FLOAT, // data type
+            gl::FALSE,      // normalized (int-to-float conversion)
+            stride as gl::types::GLint,
+            offset as *const gl::types::GLvoid,
+        );
+    }
+}
+
+// Implement a constructor for the vertex struct
+impl From<(f32, f32, f32, f32)> for VertVec4F16 {
+    fn from(tuple: (f32, f32, f32, f32)) -> Self {
+        Self::new(tuple.0, tuple.1, tuple.2, tuple.3)
+    }
+}
+
+// Function to convert f32 to bits of the nearest half float (ties to even), values too large
+// for a half float become infinity and values too small become zero or subnormals
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    // Infinity and NaN, NaN keeps a mantissa bit so it stays NaN
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x0200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    // Subnormal half floats keep the implicit leading bit in the mantissa
+    let (half, shift, mantissa) = if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign;
+        }
+        (0, (14 - half_exponent) as u32, mantissa | 0x80_0000)
+    } else {
+        ((half_exponent as u32) << 10, 13, mantissa)
+    };
+
+    let mut half = half | (mantissa >> shift);
+    let remainder = mantissa & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    // Carry out of the mantissa moves into the exponent, which is the correct rounding
+    if remainder > halfway || (remainder == halfway && half & 1 == 1) {
+        half += 1;
+    }
+
+    sign | half as u16
+}
+
+// Macro to define a vertex data type with 1 to 4 integer components of the same type, the
+// integer flavor stays integers in the shader (glVertexAttribIPointer) and the normalized
+// flavor is converted to floats in [0, 1] for unsigned and [-1, 1] for signed types
+macro_rules! integer_vertex_type {
+    ($name:ident($ty:ty, $gl_type:expr) integer { $($field:ident),+ }) => {
+        integer_vertex_type!(@struct $name($ty) { $($field),+ });
+
+        // Implement the vertex struct
+        impl $name {
+            // Attribute description used by derived vertex layouts
+            pub const ATTRIBUTE: AttributeDesc =
+                AttributeDesc::integer(integer_vertex_type!(@count $($field),+), $gl_type);
+
+            // Function which enables and sets the vertex attribute pointers
+            pub unsafe fn vertex_attrib_pointer(
+                gl: &gl::Gl,
+                stride: usize,
+                location: usize,
+                offset: usize,
+            ) {
+                // Enable the vertex attribute array at the given location
+                gl.EnableVertexAttribArray(location as gl::types::GLuint);
+                gl.VertexAttribIPointer(
+                    location as gl::types::GLuint,
+                    Self::ATTRIBUTE.components, // the number of components
+                    $gl_type,                   // data type
+                    stride as gl::types::GLint,
+                    offset as *const gl::types::GLvoid,
+                );
+            }
+        }
+    };
+    ($name:ident($ty:ty, $gl_type:expr) normalized { $($field:ident),+ }) => {
+        integer_vertex_type!(@struct $name($ty) { $($field),+ });
+
+        // Implement the vertex struct
+        impl $name {
+            // Attribute description used by derived vertex layouts
+            pub const ATTRIBUTE: AttributeDesc =
+                AttributeDesc::float(integer_vertex_type!(@count $($field),+), $gl_type, true);
+
+            // Function which enables and sets the vertex attribute pointers
+            pub unsafe fn vertex_attrib_pointer(
+                gl: &gl::Gl,
+                stride: usize,
+                location: usize,
+                offset: usize,
+            ) {
+                // Enable the vertex attribute array at the given location
+                gl.EnableVertexAttribArray(location as gl::types::GLuint);
+                gl.VertexAttribPointer(
+                    location as gl::types::GLuint,
+                    Self::ATTRIBUTE.components, // the number of components
+                    $gl_type,                   // data type
+                    gl::TRUE,                   // normalized (int-to-float conversion)
+                    stride as gl::types::GLint,
+                    offset as *const gl::types::GLvoid,
+                );
+            }
+        }
+    };
+    (@struct $name:ident($ty:ty) { $($field:ident),+ }) => {
+        #[derive(Copy, Clone, Debug)]
+        #[repr(C, packed)]
+        pub struct $name {
+            $(pub $field: $ty,)+
+        }
+
+        impl VertexAttrib for $name {}
+
+        // Implement a constructor for the vertex struct
+        impl $name {
+            // Function which creates a new vertex
+            pub fn new($($field: $ty),+) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        // Implement a constructor for the vertex struct, from a single value or a tuple
+        #[allow(unused_parens)]
+        impl From<($(integer_vertex_type!(@type $field $ty)),+)> for $name {
+            fn from(($($field),+): ($(integer_vertex_type!(@type $field $ty)),+)) -> Self {
+                Self::new($($field),+)
+            }
+        }
+    };
+    (@count $($field:ident),+) => {
+        [$(stringify!($field)),+].len() as i32
+    };
+    (@type $field:ident $ty:ty) => {
+        $ty
+    };
+}
+
+// Unsigned 16 bit integer types, e.g. bone or material indices
+integer_vertex_type!(VertU16(u16, gl::UNSIGNED_SHORT) integer { x });
+integer_vertex_type!(VertVec2U16(u16, gl::UNSIGNED_SHORT) integer { x, y });
+integer_vertex_type!(VertVec3U16(u16, gl::UNSIGNED_SHORT) integer { x, y, z });
+integer_vertex_type!(VertVec4U16(u16, gl::UNSIGNED_SHORT) integer { x, y, z, w });
+
+// Unsigned 16 bit types normalized to [0, 1], e.g. texture coordinates or weights
+integer_vertex_type!(VertU16Float(u16, gl::UNSIGNED_SHORT) normalized { x });
+integer_vertex_type!(VertVec2U16Float(u16, gl::UNSIGNED_SHORT) normalized { x, y });
+integer_vertex_type!(VertVec3U16Float(u16, gl::UNSIGNED_SHORT) normalized { x, y, z });
+integer_vertex_type!(VertVec4U16Float(u16, gl::UNSIGNED_SHORT) normalized { x, y, z, w });
+
+// Signed 16 bit integer types
+integer_vertex_type!(VertI16(i16, gl::SHORT) integer { x });
+integer_vertex_type!(VertVec2I16(i16, gl::SHORT) integer { x, y });
+integer_vertex_type!(VertVec3I16(i16, gl::SHORT) integer { x, y, z });
+integer_vertex_type!(VertVec4I16(i16, gl::SHORT) integer { x, y, z, w });
+
+// Signed 16 bit types normalized to [-1, 1], e.g. compact normals or tangents
+integer_vertex_type!(VertI16Float(i16, gl::SHORT) normalized { x });
+integer_vertex_type!(VertVec2I16Float(i16, gl::SHORT) normalized { x, y });
+integer_vertex_type!(VertVec3I16Float(i16, gl::SHORT) normalized { x, y, z });
+integer_vertex_type!(VertVec4I16Float(i16, gl::SHORT) normalized { x, y, z, w });
+
+// Unsigned 32 bit integer types, e.g. object ids for picking
+integer_vertex_type!(VertU32(u32, gl::UNSIGNED_INT) integer { x });
+integer_vertex_type!(VertVec2U32(u32, gl::UNSIGNED_INT) integer { x, y });
+integer_vertex_type!(VertVec3U32(u32, gl::UNSIGNED_INT) integer { x, y, z });
+integer_vertex_type!(VertVec4U32(u32, gl::UNSIGNED_INT) integer { x, y, z, w });
+
+// Unsigned 32 bit types normalized to [0, 1]
+integer_vertex_type!(VertU32Float(u32, gl::UNSIGNED_INT) normalized { x });
+integer_vertex_type!(VertVec2U32Float(u32, gl::UNSIGNED_INT) normalized { x, y });
+integer_vertex_type!(VertVec3U32Float(u32, gl::UNSIGNED_INT) normalized { x, y, z });
+integer_vertex_type!(VertVec4U32Float(u32, gl::UNSIGNED_INT) normalized { x, y, z, w });
+
+// Signed 32 bit integer types, e.g. grid coordinates
+integer_vertex_type!(VertI32(i32, gl::INT) integer { x });
+integer_vertex_type!(VertVec2I32(i32, gl::INT) integer { x, y });
+integer_vertex_type!(VertVec3I32(i32, gl::INT) integer { x, y, z });
+integer_vertex_type!(VertVec4I32(i32, gl::INT) integer { x, y, z, w });
+
+// Signed 32 bit types normalized to [-1, 1]
+integer_vertex_type!(VertI32Float(i32, gl::INT) normalized { x });
+integer_vertex_type!(VertVec2I32Float(i32, gl::INT) normalized { x, y });
+integer_vertex_type!(VertVec3I32Float(i32, gl::INT) normalized { x, y, z });
+integer_vertex_type!(VertVec4I32Float(i32, gl::INT) normalized { x, y, z, w });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f32_to_f16_rounds_to_nearest_even() {
+        let cases = [
+            (0.0, 0x0000),
+            (-0.0, 0x8000),
+            (1.0, 0x3c00),
+            (-2.0, 0xc000),
+            // Largest finite half float
+            (65504.0, 0x7bff),
+            // Halfway to the next (unrepresentable) value rounds to even, which is infinity
+            (65520.0, 0x7c00),
+            (f32::INFINITY, 0x7c00),
+            (f32::NEG_INFINITY, 0xfc00),
+            // Smallest subnormal and half of it, a tie rounding to even zero
+            (2f32.powi(-24), 0x0001),
+            (2f32.powi(-25), 0x0000),
+            // Smallest normal
+            (2f32.powi(-14), 0x0400),
+            // Ties between 1.0 and its neighbours round to the even mantissa
+            (1.0 + 2f32.powi(-11), 0x3c00),
+            (1.0 + 3.0 * 2f32.powi(-11), 0x3c02),
+        ];
+        for (value, half) in cases {
+            assert_eq!(f32_to_f16(value), half, "{}", value);
+        }
+    }
+
+    #[test]
+    fn f32_to_f16_keeps_nan() {
+        let half = f32_to_f16(f32::NAN);
+        assert_eq!(half & 0x7c00, 0x7c00);
+        assert_ne!(half & 0x03ff, 0);
+    }
+}
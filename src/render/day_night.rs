@@ -0,0 +1,101 @@
+// Import dependencies
+use crate::math::Vec3;
+use crate::render::sky::Sky;
+use crate::time::Time;
+
+// Color of sunlight near the horizon and high in the sky
+const SUNSET_COLOR: Vec3 = Vec3::new(1.0, 0.5, 0.25);
+const NOON_COLOR: Vec3 = Vec3::new(1.0, 0.97, 0.9);
+
+// Struct that represents a day/night cycle, moves the sun over a day of configurable length
+// and derives sky and sun light parameters from its position
+#[derive(Copy, Clone, Debug)]
+pub struct DayNight {
+    // Fraction of the day, 0 is midnight, 0.25 sunrise, 0.5 noon and 0.75 sunset
+    time_of_day: f32,
+    // Length of a whole day in seconds of scaled time
+    day_length: f32,
+    // Angle in radians the sun path is tilted towards the south (-z) from the zenith
+    tilt: f32,
+    // Atmosphere turbidity applied to the sky
+    turbidity: f32,
+}
+
+// Implementation of day night
+impl DayNight {
+    // Constructor for a cycle starting in the morning with a day lasting the given seconds
+    pub fn new(day_length: f32) -> DayNight {
+        DayNight {
+            time_of_day: 0.3,
+            day_length: day_length.max(f32::EPSILON),
+            tilt: 0.6,
+            turbidity: 2.5,
+        }
+    }
+
+    // Function to advance the time of day by the scaled frame time, so pausing or slowing
+    // down Time also stops or slows down the cycle
+    pub fn update(&mut self, time: &Time) {
+        self.time_of_day = (self.time_of_day + time.delta() / self.day_length).rem_euclid(1.0);
+    }
+
+    // Function to get fraction of the day, 0 is midnight and 0.5 noon
+    pub fn time_of_day(&self) -> f32 {
+        self.time_of_day
+    }
+
+    // Function to jump to a fraction of the day
+    pub fn set_time_of_day(&mut self, time_of_day: f32) {
+        self.time_of_day = time_of_day.rem_euclid(1.0);
+    }
+
+    // Function to get length of a day in seconds
+    pub fn day_length(&self) -> f32 {
+        self.day_length
+    }
+
+    // Function to set length of a day in seconds
+    pub fn set_day_length(&mut self, day_length: f32) {
+        self.day_length = day_length.max(f32::EPSILON);
+    }
+
+    // Function to set tilt of the sun path in radians, 0 passes straight overhead
+    pub fn set_tilt(&mut self, tilt: f32) {
+        self.tilt = tilt;
+    }
+
+    // Function to set atmosphere turbidity applied to the sky
+    pub fn set_turbidity(&mut self, turbidity: f32) {
+        self.turbidity = turbidity;
+    }
+
+    // Function to get direction towards the sun, rises in +x and sets in -x
+    pub fn sun_direction(&self) -> Vec3 {
+        let angle = (self.time_of_day - 0.25) * ::std::f32::consts::TAU;
+        let (height, east) = angle.sin_cos();
+        Vec3::new(east, height * self.tilt.cos(), -height * self.tilt.sin()).normalize()
+    }
+
+    // Function to get color of the sun light, reddish near the horizon
+    pub fn sun_color(&self) -> Vec3 {
+        let t = smoothstep(0.0, 0.4, self.sun_direction().y);
+        SUNSET_COLOR.lerp(NOON_COLOR, t)
+    }
+
+    // Function to get intensity of the sun light, from 0 at night to 1 with the sun up
+    pub fn sun_intensity(&self) -> f32 {
+        smoothstep(-0.05, 0.1, self.sun_direction().y)
+    }
+
+    // Function to update sky parameters from the cycle
+    pub fn apply(&self, sky: &mut Sky) {
+        sky.set_sun_direction(self.sun_direction());
+        sky.set_turbidity(self.turbidity);
+    }
+}
+
+// Helper function to smoothly step from 0 at edge0 to 1 at edge1
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
@@ -0,0 +1,74 @@
+// Import dependencies
+use gl;
+use std::collections::HashSet;
+use std::ffi::CStr;
+
+// Struct that holds the context version and supported extensions, queried once at startup
+// so optional features can be checked instead of assuming GL 4.5 everywhere
+#[derive(Clone, Debug)]
+pub struct Extensions {
+    // Major and minor context version
+    version: (u32, u32),
+    // Names of supported extensions, e.g. "GL_ARB_bindless_texture"
+    names: HashSet<String>,
+}
+
+// Implementation of extensions
+impl Extensions {
+    // Constructor which queries the current context
+    pub fn new(gl: &gl::Gl) -> Extensions {
+        let mut major: gl::types::GLint = 0;
+        let mut minor: gl::types::GLint = 0;
+        let mut count: gl::types::GLint = 0;
+        unsafe {
+            gl.GetIntegerv(gl::MAJOR_VERSION, &mut major);
+            gl.GetIntegerv(gl::MINOR_VERSION, &mut minor);
+            gl.GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+        }
+
+        let names = (0..count.max(0) as gl::types::GLuint)
+            .filter_map(|i| {
+                let name = unsafe { gl.GetStringi(gl::EXTENSIONS, i) };
+                if name.is_null() {
+                    return None;
+                }
+                Some(
+                    unsafe { CStr::from_ptr(name as *const _) }
+                        .to_string_lossy()
+                        .into_owned(),
+                )
+            })
+            .collect();
+
+        Extensions {
+            version: (major as u32, minor as u32),
+            names,
+        }
+    }
+
+    // Function to get the context version as (major, minor)
+    pub fn version(&self) -> (u32, u32) {
+        self.version
+    }
+
+    // Function to check if the context version is at least the given one
+    pub fn is_version_at_least(&self, major: u32, minor: u32) -> bool {
+        self.version >= (major, minor)
+    }
+
+    // Function to check if an extension is supported
+    pub fn has(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    // Function to check for graphics reset detection (core since 4.5), GL_ARB_robustness is
+    // not enough since the bindings only load the core, EXT and KHR entry points
+    pub fn has_robustness(&self) -> bool {
+        self.is_version_at_least(4, 5) || self.has("GL_KHR_robustness")
+    }
+
+    // Function to check for SPIR-V shader binaries (core since 4.6)
+    pub fn has_spirv(&self) -> bool {
+        self.is_version_at_least(4, 6) || self.has("GL_ARB_gl_spirv")
+    }
+}
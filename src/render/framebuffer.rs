@@ -0,0 +1,497 @@
+// Import dependencies
+use gl;
+
+use crate::render::state::StateScope;
+use crate::render::texture::{InternalFormat, Texture2D, Texture2DMultisample};
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(display = "Framebuffer has an attachment that can't be rendered to")]
+    IncompleteAttachment,
+    #[fail(display = "Framebuffer has no attachments")]
+    MissingAttachment,
+    #[fail(display = "Framebuffer draws into an attachment point without an attachment")]
+    IncompleteDrawBuffer,
+    #[fail(display = "Framebuffer reads from an attachment point without an attachment")]
+    IncompleteReadBuffer,
+    #[fail(display = "Framebuffer attachment formats are not supported together")]
+    Unsupported,
+    #[fail(display = "Framebuffer attachments have different sample counts")]
+    IncompleteMultisample,
+    #[fail(display = "Framebuffer mixes layered and non-layered attachments")]
+    IncompleteLayerTargets,
+    #[fail(display = "Framebuffer is incomplete (status {:#x})", status)]
+    Unknown { status: gl::types::GLenum },
+}
+
+// Enum that represents the point an image is attached to
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Attachment {
+    // Color output N of the fragment shader ("layout (location = N) out")
+    Color(u32),
+    Depth,
+    Stencil,
+    // Combined depth and stencil, for Depth24Stencil8
+    DepthStencil,
+}
+
+// Implementation of attachment
+impl Attachment {
+    // Function to get the matching gl enum value
+    pub fn to_gl(self) -> gl::types::GLenum {
+        match self {
+            Attachment::Color(index) => gl::COLOR_ATTACHMENT0 + index,
+            Attachment::Depth => gl::DEPTH_ATTACHMENT,
+            Attachment::Stencil => gl::STENCIL_ATTACHMENT,
+            Attachment::DepthStencil => gl::DEPTH_STENCIL_ATTACHMENT,
+        }
+    }
+}
+
+// Struct that represents a renderbuffer, an image that can be rendered to but not sampled,
+// e.g. a depth buffer only used for depth testing
+pub struct Renderbuffer {
+    // The gl context
+    gl: gl::Gl,
+    // The id of the renderbuffer object
+    id: gl::types::GLuint,
+    // Size of the renderbuffer in pixels
+    width: u32,
+    height: u32,
+    // Storage format of the renderbuffer
+    format: InternalFormat,
+    // Number of samples per pixel, 0 if not multisampled
+    samples: u32,
+}
+
+// Implementation of renderbuffer
+impl Renderbuffer {
+    // Function to create renderbuffer with uninitialized storage
+    pub fn allocate(gl: &gl::Gl, width: u32, height: u32, format: InternalFormat) -> Renderbuffer {
+        Renderbuffer::allocate_multisample(gl, width, height, format, 0)
+    }
+
+    // Function to create multisampled renderbuffer with uninitialized storage, 0 samples
+    // creates a regular renderbuffer
+    pub fn allocate_multisample(
+        gl: &gl::Gl,
+        width: u32,
+        height: u32,
+        format: InternalFormat,
+        samples: u32,
+    ) -> Renderbuffer {
+        let mut id: gl::types::GLuint = 0;
+        unsafe {
+            gl.GenRenderbuffers(1, &mut id);
+            gl.BindRenderbuffer(gl::RENDERBUFFER, id);
+            gl.RenderbufferStorageMultisample(
+                gl::RENDERBUFFER,
+                samples as gl::types::GLsizei,
+                format.to_gl(),
+                width as gl::types::GLsizei,
+                height as gl::types::GLsizei,
+            );
+            gl.BindRenderbuffer(gl::RENDERBUFFER, 0);
+        }
+
+        Renderbuffer {
+            gl: gl.clone(),
+            id,
+            width,
+            height,
+            format,
+            samples,
+        }
+    }
+
+    // Function to get the renderbuffer id
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    // Function to get width of the renderbuffer in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    // Function to get height of the renderbuffer in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // Function to get storage format of the renderbuffer
+    pub fn format(&self) -> InternalFormat {
+        self.format
+    }
+
+    // Function to get number of samples per pixel, 0 if not multisampled
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+}
+
+// Drop trait implementation for renderbuffer
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteRenderbuffers(1, &self.id);
+        }
+    }
+}
+
+// Enum that represents the image behind an attachment
+pub enum AttachmentImage {
+    // Texture which can be sampled after rendering, e.g. for post-processing
+    Texture(Texture2D),
+    // Multisampled texture, read with texelFetch or resolved
+    MultisampleTexture(Texture2DMultisample),
+    // Renderbuffer which is only rendered to
+    Renderbuffer(Renderbuffer),
+}
+
+// Implementation of attachment image
+impl AttachmentImage {
+    // Function to get storage format of the image
+    pub fn format(&self) -> InternalFormat {
+        match self {
+            AttachmentImage::Texture(texture) => texture.format(),
+            AttachmentImage::MultisampleTexture(texture) => texture.format(),
+            AttachmentImage::Renderbuffer(renderbuffer) => renderbuffer.format(),
+        }
+    }
+}
+
+// Struct that represents a framebuffer object which owns its attachments, so it can render
+// into textures and recreate them all at a new size
+pub struct Framebuffer {
+    // The gl context
+    gl: gl::Gl,
+    // The id of the framebuffer object
+    id: gl::types::GLuint,
+    // Size of every attachment in pixels
+    width: u32,
+    height: u32,
+    // Number of samples per pixel of every attachment, 0 if not multisampled
+    samples: u32,
+    // Attached images and their attachment points
+    attachments: Vec<(Attachment, AttachmentImage)>,
+}
+
+// Implementation of framebuffer
+impl Framebuffer {
+    // Constructor for a framebuffer without attachments, attachments are created at the
+    // given size
+    pub fn new(gl: &gl::Gl, width: u32, height: u32) -> Framebuffer {
+        Framebuffer::new_multisample(gl, width, height, 0)
+    }
+
+    // Constructor for a framebuffer whose attachments are created with the given number of
+    // samples per pixel, it is drawn into with antialiasing and then resolved with resolve_to
+    pub fn new_multisample(gl: &gl::Gl, width: u32, height: u32, samples: u32) -> Framebuffer {
+        let mut id: gl::types::GLuint = 0;
+        unsafe {
+            gl.GenFramebuffers(1, &mut id);
+        }
+
+        Framebuffer {
+            gl: gl.clone(),
+            id,
+            width,
+            height,
+            samples,
+            attachments: Vec::new(),
+        }
+    }
+
+    // Function to create a texture of the given format and attach it, replacing the image
+    // previously attached to the same point
+    pub fn attach_texture(&mut self, attachment: Attachment, format: InternalFormat) {
+        let (width, height) = (self.width, self.height);
+        let image = if self.samples > 0 {
+            let texture =
+                Texture2DMultisample::allocate(&self.gl, width, height, format, self.samples);
+            AttachmentImage::MultisampleTexture(texture)
+        } else {
+            AttachmentImage::Texture(Texture2D::allocate(&self.gl, width, height, format))
+        };
+        self.attach(attachment, image);
+    }
+
+    // Function to create a renderbuffer of the given format and attach it, replacing the
+    // image previously attached to the same point
+    pub fn attach_renderbuffer(&mut self, attachment: Attachment, format: InternalFormat) {
+        let (width, height) = (self.width, self.height);
+        let renderbuffer =
+            Renderbuffer::allocate_multisample(&self.gl, width, height, format, self.samples);
+        self.attach(attachment, AttachmentImage::Renderbuffer(renderbuffer));
+    }
+
+    // Function to check if the framebuffer can be rendered to with its current attachments
+    pub fn check(&self) -> Result<(), Error> {
+        let status = unsafe {
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, self.id);
+            let status = self.gl.CheckFramebufferStatus(gl::FRAMEBUFFER);
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+            status
+        };
+
+        match status {
+            gl::FRAMEBUFFER_COMPLETE => Ok(()),
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => Err(Error::IncompleteAttachment),
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => Err(Error::MissingAttachment),
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => Err(Error::IncompleteDrawBuffer),
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => Err(Error::IncompleteReadBuffer),
+            gl::FRAMEBUFFER_UNSUPPORTED => Err(Error::Unsupported),
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => Err(Error::IncompleteMultisample),
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => Err(Error::IncompleteLayerTargets),
+            status => Err(Error::Unknown { status }),
+        }
+    }
+
+    // Function to bind the framebuffer for drawing and reading, the viewport is not changed
+    pub fn bind(&self) {
+        unsafe {
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, self.id);
+        }
+    }
+
+    // Function to bind the default framebuffer (the window) again
+    pub fn unbind(&self) {
+        unsafe {
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    // Function to recreate every attachment at a new size with the same formats, e.g. after
+    // the window was resized (contents are lost, sampling parameters of textures are kept)
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        if (width, height) == (self.width, self.height) {
+            return Ok(());
+        }
+        self.width = width;
+        self.height = height;
+
+        let attachments = ::std::mem::take(&mut self.attachments);
+        for (attachment, image) in attachments {
+            match image {
+                AttachmentImage::Texture(texture) => {
+                    self.attach_texture(attachment, texture.format());
+                    if let Some(resized) = self.texture(attachment) {
+                        resized.copy_parameters_from(&texture);
+                    }
+                }
+                AttachmentImage::MultisampleTexture(texture) => {
+                    self.attach_texture(attachment, texture.format())
+                }
+                AttachmentImage::Renderbuffer(renderbuffer) => {
+                    self.attach_renderbuffer(attachment, renderbuffer.format())
+                }
+            }
+        }
+
+        self.check()
+    }
+
+    // Function to copy the contents into another framebuffer of the same size, resolving
+    // multisampled attachments to one sample per pixel. Mask selects the buffers to copy,
+    // e.g. gl::COLOR_BUFFER_BIT (color attachment 0) or adding gl::DEPTH_BUFFER_BIT.
+    // Resolved multisampled attachments are invalidated, their samples are not needed anymore.
+    pub fn resolve_to(&self, target: &Framebuffer, mask: gl::types::GLbitfield) {
+        self.blit(target.id, target.width, target.height, mask);
+        self.invalidate_resolved(mask);
+    }
+
+    // Function to copy the contents into the window, which has to be the same size
+    pub fn resolve_to_window(&self, width: u32, height: u32, mask: gl::types::GLbitfield) {
+        self.blit(0, width, height, mask);
+        self.invalidate_resolved(mask);
+    }
+
+    // Function to tell the driver the contents of attachments are not needed anymore, so
+    // tiled gpus can skip writing them back to memory and others can skip decompression
+    pub fn invalidate(&self, attachments: &[Attachment]) {
+        let attachments: Vec<gl::types::GLenum> = attachments
+            .iter()
+            .map(|attachment| attachment.to_gl())
+            .collect();
+        // Named (dsa) variant so the bindings of the caller stay untouched
+        unsafe {
+            self.gl.InvalidateNamedFramebufferData(
+                self.id,
+                attachments.len() as gl::types::GLsizei,
+                attachments.as_ptr(),
+            );
+        }
+    }
+
+    // Function to get the image attached to a point
+    pub fn image(&self, attachment: Attachment) -> Option<&AttachmentImage> {
+        self.attachments
+            .iter()
+            .find(|(point, _)| *point == attachment)
+            .map(|(_, image)| image)
+    }
+
+    // Function to get the texture attached to a point, e.g. to sample the rendered colors
+    pub fn texture(&self, attachment: Attachment) -> Option<&Texture2D> {
+        match self.image(attachment) {
+            Some(AttachmentImage::Texture(texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    // Function to get the framebuffer id
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    // Function to get width of the attachments in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    // Function to get height of the attachments in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // Function to get number of samples per pixel, 0 if not multisampled
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    // Helper function to blit into a framebuffer, depth and stencil can only be copied
+    // without filtering and resolving requires equal sizes, so nearest is always used
+    fn blit(
+        &self,
+        target: gl::types::GLuint,
+        width: u32,
+        height: u32,
+        mask: gl::types::GLbitfield,
+    ) {
+        let mut state = StateScope::new(&self.gl);
+        state.save_framebuffer();
+        unsafe {
+            self.gl.BindFramebuffer(gl::READ_FRAMEBUFFER, self.id);
+            self.gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, target);
+            self.gl.BlitFramebuffer(
+                0,
+                0,
+                self.width as gl::types::GLint,
+                self.height as gl::types::GLint,
+                0,
+                0,
+                width as gl::types::GLint,
+                height as gl::types::GLint,
+                mask,
+                gl::NEAREST,
+            );
+        }
+    }
+
+    // Helper function to invalidate the multisampled attachments read by a resolve with mask,
+    // colors are read from color attachment 0 (see attach)
+    fn invalidate_resolved(&self, mask: gl::types::GLbitfield) {
+        if self.samples == 0 {
+            return;
+        }
+
+        let depth = mask & gl::DEPTH_BUFFER_BIT != 0;
+        let stencil = mask & gl::STENCIL_BUFFER_BIT != 0;
+        let resolved: Vec<Attachment> = self
+            .attachments
+            .iter()
+            .filter_map(|(point, _)| match point {
+                Attachment::Color(0) if mask & gl::COLOR_BUFFER_BIT != 0 => Some(*point),
+                Attachment::Depth if depth => Some(*point),
+                Attachment::Stencil if stencil => Some(*point),
+                // A combined image is only dropped as a whole if both parts were resolved,
+                // otherwise just the resolved part is invalidated
+                Attachment::DepthStencil => match (depth, stencil) {
+                    (true, true) => Some(Attachment::DepthStencil),
+                    (true, false) => Some(Attachment::Depth),
+                    (false, true) => Some(Attachment::Stencil),
+                    (false, false) => None,
+                },
+                _ => None,
+            })
+            .collect();
+        if !resolved.is_empty() {
+            self.invalidate(&resolved);
+        }
+    }
+
+    // Function to attach an image and route fragment outputs to every color attachment
+    fn attach(&mut self, attachment: Attachment, image: AttachmentImage) {
+        unsafe {
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, self.id);
+            match &image {
+                AttachmentImage::Texture(texture) => self.gl.FramebufferTexture2D(
+                    gl::FRAMEBUFFER,
+                    attachment.to_gl(),
+                    gl::TEXTURE_2D,
+                    texture.id(),
+                    0, // mipmap level
+                ),
+                AttachmentImage::MultisampleTexture(texture) => self.gl.FramebufferTexture2D(
+                    gl::FRAMEBUFFER,
+                    attachment.to_gl(),
+                    gl::TEXTURE_2D_MULTISAMPLE,
+                    texture.id(),
+                    0, // mipmap level
+                ),
+                AttachmentImage::Renderbuffer(renderbuffer) => self.gl.FramebufferRenderbuffer(
+                    gl::FRAMEBUFFER,
+                    attachment.to_gl(),
+                    gl::RENDERBUFFER,
+                    renderbuffer.id(),
+                ),
+            }
+        }
+
+        self.attachments.retain(|(point, _)| *point != attachment);
+        self.attachments.push((attachment, image));
+
+        // Output N of the fragment shader goes to color attachment N, depth-only framebuffers
+        // (e.g. shadow maps) have no color output at all
+        let count = self
+            .attachments
+            .iter()
+            .filter_map(|(point, _)| match point {
+                Attachment::Color(index) => Some(index + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        let buffers: Vec<gl::types::GLenum> = (0..count)
+            .map(|index| {
+                if self.image(Attachment::Color(index)).is_some() {
+                    Attachment::Color(index).to_gl()
+                } else {
+                    gl::NONE
+                }
+            })
+            .collect();
+        unsafe {
+            if buffers.is_empty() {
+                self.gl.DrawBuffer(gl::NONE);
+                self.gl.ReadBuffer(gl::NONE);
+            } else {
+                self.gl
+                    .DrawBuffers(buffers.len() as gl::types::GLsizei, buffers.as_ptr());
+                self.gl.ReadBuffer(buffers[0]);
+            }
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+}
+
+// Drop trait implementation for framebuffer
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteFramebuffers(1, &self.id);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+// Import dependencies
+use gl;
+
+use crate::render::buffer::ShaderStorageBuffer;
+use crate::render::compute::{self, ComputeProgram};
+use crate::render::texture::Texture2D;
+use crate::render::Error;
+use crate::resources::Resources;
+
+// Number of histogram bins, matches BIN_COUNT in shaders/histogram.comp
+pub const BIN_COUNT: usize = 256;
+
+// Texture unit the analyzed texture is bound to
+const SOURCE_UNIT: u32 = 0;
+
+// Enum that represents how luminance values are spread over the histogram bins
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HistogramRange {
+    // Bins evenly cover luminance from min to max, e.g. 0 to 1 for LDR textures
+    Linear { min: f32, max: f32 },
+    // Bins evenly cover log2 luminance from min to max, e.g. -10 to 10 for HDR targets
+    Log2 { min: f32, max: f32 },
+}
+
+// Struct that represents luminance statistics of a texture
+#[derive(Clone, Debug)]
+pub struct ImageStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    // Number of texels in each bin, values outside of the range go to the first or last bin
+    pub histogram: Vec<u32>,
+    // Range the histogram bins cover
+    pub range: HistogramRange,
+}
+
+// Struct that represents a compute pass computing luminance statistics of textures, e.g. to
+// check HDR targets before and after tone mapping. Results are read back right away, which
+// waits for the gpu, so it is meant for debugging rather than every frame.
+pub struct ImageAnalyzer {
+    // The gl context
+    gl: gl::Gl,
+    // Program compiled from shaders/histogram.comp
+    compute: ComputeProgram,
+    // Min, max and bins
+    counters: ShaderStorageBuffer,
+    // Luminance sum of each work group
+    sums: ShaderStorageBuffer,
+}
+
+// Implementation of image analyzer
+impl ImageAnalyzer {
+    // Function to create analyzer from the shaders/histogram compute shader
+    pub fn from_res(gl: &gl::Gl, res: &Resources) -> Result<ImageAnalyzer, Error> {
+        Ok(ImageAnalyzer {
+            gl: gl.clone(),
+            compute: ComputeProgram::from_res(gl, res, "shaders/histogram")?,
+            counters: ShaderStorageBuffer::new(gl),
+            sums: ShaderStorageBuffer::new(gl),
+        })
+    }
+
+    // Function to compute luminance statistics of the base level of a texture
+    pub fn analyze(&self, texture: &Texture2D, range: HistogramRange) -> ImageStats {
+        let (width, height) = (texture.width(), texture.height());
+        let [group_x, group_y, _] = self.compute.work_group_size();
+        let groups_x = width.div_ceil(group_x.max(1));
+        let groups_y = height.div_ceil(group_y.max(1));
+        let groups = (groups_x * groups_y) as usize;
+
+        // Minimum starts at the bits of infinity and maximum at zero
+        let mut counters: Vec<u32> = vec![0; 2 + BIN_COUNT];
+        counters[0] = f32::INFINITY.to_bits();
+        self.counters.bind();
+        self.counters.dynamic_draw_data(&counters);
+        self.counters.unbind();
+        self.sums.bind();
+        self.sums.dynamic_draw_data(&vec![0f32; groups]);
+        self.sums.unbind();
+
+        let (range_min, range_max, log_scale) = match range {
+            HistogramRange::Linear { min, max } => (min, max, false),
+            HistogramRange::Log2 { min, max } => (min, max, true),
+        };
+        let program = self.compute.program();
+        self.compute.set_used();
+        program.set_uniform_1i("Source", SOURCE_UNIT as i32);
+        program.set_uniform_1f("RangeMin", range_min);
+        program.set_uniform_1f("RangeMax", range_max);
+        program.set_uniform_bool("LogScale", log_scale);
+
+        texture.bind_at(SOURCE_UNIT);
+        self.compute.bind_storage_buffer(0, &self.counters);
+        self.compute.bind_storage_buffer(1, &self.sums);
+        self.compute.dispatch(groups_x, groups_y, 1);
+        texture.unbind();
+
+        // Make the shader writes visible to the read back
+        compute::memory_barrier(&self.gl, gl::BUFFER_UPDATE_BARRIER_BIT);
+
+        self.counters.bind();
+        self.counters.get_sub_data(0, &mut counters);
+        self.counters.unbind();
+        let mut sums = vec![0f32; groups];
+        self.sums.bind();
+        self.sums.get_sub_data(0, &mut sums);
+        self.sums.unbind();
+
+        let texels = (width as u64 * height as u64).max(1);
+        let sum: f64 = sums.iter().map(|&sum| sum as f64).sum();
+        let (min, max) = if width * height == 0 {
+            (0.0, 0.0)
+        } else {
+            (f32::from_bits(counters[0]), f32::from_bits(counters[1]))
+        };
+
+        ImageStats {
+            min,
+            max,
+            mean: (sum / texels as f64) as f32,
+            histogram: counters[2..].to_vec(),
+            range,
+        }
+    }
+}
@@ -0,0 +1,244 @@
+// Import dependencies
+use gl;
+
+use crate::render::buffer::{draw_arrays_instanced, ArrayBuffer, IndexBuffer, VertexArray};
+use crate::render::shader::validate_before_draw;
+use crate::render::{data, Vertex};
+use crate::resources::model::{Model, ModelMesh};
+
+// Vertex layout used for meshes created from loaded models
+#[derive(VertexAttribPointers, Copy, Clone, Debug)]
+#[repr(C, packed)]
+pub struct ModelVertex {
+    #[location = "0"]
+    pub pos: data::VertVec3D,
+    #[location = "1"]
+    pub normal: data::VertVec3D,
+    #[location = "2"]
+    pub tex_coord: data::VertVec2D,
+}
+
+// Struct that represents drawable geometry owning its vertex array and buffers
+pub struct Mesh {
+    // The gl context
+    gl: gl::Gl,
+    // Vertex data
+    vbo: ArrayBuffer,
+    // Optional index data
+    ebo: Option<IndexBuffer<u32>>,
+    // Vertex array recording attribute layout and index buffer binding
+    vao: VertexArray,
+    // Vertex array recording only the index buffer binding, for vertex pulling
+    pulling_vao: VertexArray,
+    // Size of one vertex in bytes
+    vertex_size: usize,
+    // The number of vertices uploaded
+    vertex_count: usize,
+    // Primitive type used for drawing
+    mode: gl::types::GLenum,
+    // Number of vertices per patch, used when drawing gl::PATCHES
+    patch_vertices: u32,
+    // Optional per-instance data
+    instance_vbo: Option<ArrayBuffer>,
+}
+
+// Implementation of mesh
+impl Mesh {
+    // Function to create mesh drawn directly from vertices, attribute layout comes from
+    // the Vertex implementation generated with #[derive(VertexAttribPointers)]
+    pub fn new<V: Vertex>(gl: &gl::Gl, vertices: &[V]) -> Mesh {
+        Mesh::create(gl, vertices, None)
+    }
+
+    // Function to create mesh drawn from indices into vertices
+    pub fn with_indices<V: Vertex>(gl: &gl::Gl, vertices: &[V], indices: &[u32]) -> Mesh {
+        Mesh::create(gl, vertices, Some(indices))
+    }
+
+    // Function to create mesh from a loaded model part, missing normals and texture
+    // coordinates are filled with zeros
+    pub fn from_model_mesh(gl: &gl::Gl, model_mesh: &ModelMesh) -> Mesh {
+        let vertices = (0..model_mesh.positions.len())
+            .map(|i| {
+                let [x, y, z] = model_mesh.positions[i];
+                let [nx, ny, nz] = model_mesh.normals.get(i).copied().unwrap_or_default();
+                let [u, v] = model_mesh.tex_coords.get(i).copied().unwrap_or_default();
+                ModelVertex {
+                    pos: (x, y, z).into(),
+                    normal: (nx, ny, nz).into(),
+                    tex_coord: (u, v).into(),
+                }
+            })
+            .collect::<Vec<ModelVertex>>();
+
+        Mesh::with_indices(gl, &vertices, &model_mesh.indices)
+    }
+
+    // Function to create one mesh per part of a loaded model
+    pub fn from_model(gl: &gl::Gl, model: &Model) -> Vec<Mesh> {
+        model
+            .meshes
+            .iter()
+            .map(|model_mesh| Mesh::from_model_mesh(gl, model_mesh))
+            .collect()
+    }
+
+    fn create<V: Vertex>(gl: &gl::Gl, vertices: &[V], indices: Option<&[u32]>) -> Mesh {
+        // Upload vertex data
+        let vbo = ArrayBuffer::new(gl);
+        vbo.bind();
+        vbo.static_draw_data(vertices);
+        vbo.unbind();
+
+        // Upload index data
+        let ebo = indices.map(|indices| IndexBuffer::from_indices(gl, indices));
+
+        // Record attribute layout and index buffer in vertex array
+        let vao = VertexArray::new(gl);
+        vao.bind();
+        vbo.bind();
+        V::setup_attribs(gl);
+        if let Some(ref ebo) = ebo {
+            ebo.bind();
+        }
+        vao.unbind();
+        vbo.unbind();
+        // Vertex pulling reads vertices in the shader, so only indices are recorded
+        let pulling_vao = VertexArray::new(gl);
+        pulling_vao.bind();
+        if let Some(ref ebo) = ebo {
+            ebo.bind();
+        }
+        pulling_vao.unbind();
+        // Index buffer is unbound only after vertex array, otherwise it would be detached from it
+        if let Some(ref ebo) = ebo {
+            ebo.unbind();
+        }
+
+        Mesh {
+            gl: gl.clone(),
+            vbo,
+            ebo,
+            vao,
+            pulling_vao,
+            vertex_size: ::std::mem::size_of::<V>(),
+            vertex_count: vertices.len(),
+            mode: gl::TRIANGLES,
+            patch_vertices: 3,
+            instance_vbo: None,
+        }
+    }
+
+    // Function to set primitive type used for drawing (gl::TRIANGLES by default)
+    pub fn set_mode(&mut self, mode: gl::types::GLenum) {
+        self.mode = mode;
+    }
+
+    // Function to draw the mesh as patches of the given number of vertices, for programs
+    // with tessellation stages (e.g. 4 for quad terrain tiles, 16 for bicubic surfaces)
+    pub fn set_patches(&mut self, patch_vertices: u32) {
+        self.mode = gl::PATCHES;
+        self.patch_vertices = patch_vertices;
+    }
+
+    // Function to upload per-instance data into a second vertex buffer, attribute layout
+    // comes from the derived Vertex implementation of the instance struct whose fields use
+    // #[divisor = 1] and locations not taken by the vertex struct
+    pub fn set_instances<I: Vertex>(&mut self, instances: &[I]) {
+        let instance_vbo = self
+            .instance_vbo
+            .get_or_insert_with(|| ArrayBuffer::new(&self.gl));
+
+        self.vao.bind();
+        instance_vbo.bind();
+        instance_vbo.static_draw_data(instances);
+        I::setup_attribs(&self.gl);
+        self.vao.unbind();
+        instance_vbo.unbind();
+    }
+
+    // Function to get the instance buffer, if any
+    pub fn instance_buffer(&self) -> Option<&ArrayBuffer> {
+        self.instance_vbo.as_ref()
+    }
+
+    // Function to get the number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    // Function to get the size of one vertex in bytes, the stride for vertex pulling
+    pub fn vertex_size(&self) -> usize {
+        self.vertex_size
+    }
+
+    // Function to get the vertex buffer
+    pub fn vertex_buffer(&self) -> &ArrayBuffer {
+        &self.vbo
+    }
+
+    // Function to get the index buffer, if any
+    pub fn index_buffer(&self) -> Option<&IndexBuffer<u32>> {
+        self.ebo.as_ref()
+    }
+
+    // Function to draw the mesh with the currently used program
+    pub fn draw(&self) {
+        self.draw_vertex_array(&self.vao);
+    }
+
+    // Function to draw the mesh without vertex attributes, the vertex buffer is bound as
+    // shader storage buffer at binding and the vertex shader reads its vertex at
+    // gl_VertexID (indices are still applied), e.g. for meshlet experiments or to compare
+    // with the attribute path. Vertices are tightly packed, so shaders usually declare
+    // `layout (std430, binding = N) readonly buffer Vertices { float data[]; };` and read
+    // vertex_size() / 4 floats per vertex.
+    pub fn draw_pulled(&self, binding: u32) {
+        unsafe {
+            self.gl
+                .BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding, self.vbo.id());
+        }
+        self.draw_vertex_array(&self.pulling_vao);
+    }
+
+    // Helper function to draw all vertices or indices through a vertex array
+    fn draw_vertex_array(&self, vao: &VertexArray) {
+        // Patch size is context state, so it is set for every patch draw
+        if self.mode == gl::PATCHES {
+            unsafe {
+                self.gl
+                    .PatchParameteri(gl::PATCH_VERTICES, self.patch_vertices as gl::types::GLint);
+            }
+        }
+        vao.bind();
+        match self.ebo {
+            Some(ref ebo) => ebo.draw_elements(self.mode),
+            None => unsafe {
+                validate_before_draw(&self.gl);
+                self.gl.DrawArrays(
+                    self.mode,                               // mode
+                    0,                                       // starting index in the enabled arrays
+                    self.vertex_count as gl::types::GLsizei, // number of vertices to be rendered
+                );
+            },
+        }
+        vao.unbind();
+    }
+
+    // Function to draw the given number of instances of the mesh with the currently used
+    // program
+    pub fn draw_instanced(&self, instances: usize) {
+        if self.mode == gl::PATCHES {
+            unsafe {
+                self.gl
+                    .PatchParameteri(gl::PATCH_VERTICES, self.patch_vertices as gl::types::GLint);
+            }
+        }
+        self.vao.bind();
+        match self.ebo {
+            Some(ref ebo) => ebo.draw_elements_instanced(self.mode, instances),
+            None => draw_arrays_instanced(&self.gl, self.mode, 0, self.vertex_count, instances),
+        }
+        self.vao.unbind();
+    }
+}
@@ -0,0 +1,246 @@
+// Import dependencies
+use gl;
+
+use crate::math::Mat4;
+use crate::render::buffer::{ShaderStorageBuffer, Usage, VertexArray};
+use crate::render::camera::Camera;
+use crate::render::compute::{self, ComputeProgram};
+use crate::render::mesh::ModelVertex;
+use crate::render::shader::validate_before_draw;
+use crate::render::Error;
+use crate::resources::meshlet::Meshlet;
+use crate::resources::model::ModelMesh;
+use crate::resources::Resources;
+
+// Meshlet as read by shaders/meshlet_cull.comp (std430)
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+struct GpuMeshlet {
+    center_radius: [f32; 4],
+    cone_axis_cutoff: [f32; 4],
+    vertex_offset: u32,
+    triangle_offset: u32,
+    vertex_count: u32,
+    triangle_count: u32,
+}
+
+// Arguments of glDrawElementsIndirect, written by the culling shader
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+struct DrawCommand {
+    count: u32,
+    instance_count: u32,
+    first_index: u32,
+    base_vertex: u32,
+    base_instance: u32,
+}
+
+// Struct that represents a mesh split into meshlets which are culled on the gpu, the
+// visible triangles are drawn with one indirect draw call and vertex pulling
+pub struct MeshletMesh {
+    // The gl context
+    gl: gl::Gl,
+    // Vertex data, read by the vertex shader at gl_VertexID
+    vertices: ShaderStorageBuffer,
+    // Bounds and ranges of every meshlet
+    meshlets: ShaderStorageBuffer,
+    // Mesh vertex of each meshlet vertex
+    meshlet_vertices: ShaderStorageBuffer,
+    // Packed triangles of all meshlets
+    meshlet_triangles: ShaderStorageBuffer,
+    // Indices of visible triangles, written by the culling shader
+    indices: ShaderStorageBuffer,
+    // Indirect draw command, written by the culling shader
+    command: ShaderStorageBuffer,
+    // Vertex array recording only the index buffer
+    vao: VertexArray,
+    // The number of meshlets
+    meshlet_count: usize,
+    // Size of one vertex in bytes
+    vertex_size: usize,
+}
+
+// Implementation of meshlet mesh
+impl MeshletMesh {
+    // Function to create meshlet mesh from vertices and meshlets built from them (see
+    // resources::meshlet::build)
+    pub fn new<V: Copy>(gl: &gl::Gl, vertices: &[V], meshlets: &[Meshlet]) -> MeshletMesh {
+        let mut gpu_meshlets = Vec::with_capacity(meshlets.len());
+        let mut meshlet_vertices = Vec::new();
+        let mut meshlet_triangles = Vec::new();
+        for meshlet in meshlets {
+            let [x, y, z] = meshlet.center;
+            let [ax, ay, az] = meshlet.cone_axis;
+            gpu_meshlets.push(GpuMeshlet {
+                center_radius: [x, y, z, meshlet.radius],
+                cone_axis_cutoff: [ax, ay, az, meshlet.cone_cutoff],
+                vertex_offset: meshlet_vertices.len() as u32,
+                triangle_offset: meshlet_triangles.len() as u32,
+                vertex_count: meshlet.vertices.len() as u32,
+                triangle_count: meshlet.triangles.len() as u32,
+            });
+            meshlet_vertices.extend_from_slice(&meshlet.vertices);
+            meshlet_triangles.extend(
+                meshlet
+                    .triangles
+                    .iter()
+                    .map(|&[a, b, c]| a as u32 | (b as u32) << 8 | (c as u32) << 16),
+            );
+        }
+
+        let vertex_buffer = storage_buffer(gl, vertices);
+        let meshlet_buffer = storage_buffer(gl, &gpu_meshlets);
+        let meshlet_vertex_buffer = storage_buffer(gl, &meshlet_vertices);
+        let triangle_buffer = storage_buffer(gl, &meshlet_triangles);
+        // Room for every triangle, in case all meshlets are visible
+        let index_size = meshlet_triangles.len() * 3 * ::std::mem::size_of::<u32>();
+        let index_buffer = storage_allocation(gl, index_size);
+        let command_buffer = storage_allocation(gl, ::std::mem::size_of::<DrawCommand>());
+
+        // The index buffer is bound by id, the same buffer object can serve any target
+        let vao = VertexArray::new(gl);
+        vao.bind();
+        unsafe {
+            gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, index_buffer.id());
+        }
+        vao.unbind();
+        unsafe {
+            gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+        }
+
+        MeshletMesh {
+            gl: gl.clone(),
+            vertices: vertex_buffer,
+            meshlets: meshlet_buffer,
+            meshlet_vertices: meshlet_vertex_buffer,
+            meshlet_triangles: triangle_buffer,
+            indices: index_buffer,
+            command: command_buffer,
+            vao,
+            meshlet_count: meshlets.len(),
+            vertex_size: ::std::mem::size_of::<V>(),
+        }
+    }
+
+    // Function to create meshlet mesh from a loaded model part, with the vertex layout of
+    // mesh::ModelVertex
+    pub fn from_model_mesh(gl: &gl::Gl, model_mesh: &ModelMesh) -> MeshletMesh {
+        let vertices = (0..model_mesh.positions.len())
+            .map(|i| {
+                let [x, y, z] = model_mesh.positions[i];
+                let [nx, ny, nz] = model_mesh.normals.get(i).copied().unwrap_or_default();
+                let [u, v] = model_mesh.tex_coords.get(i).copied().unwrap_or_default();
+                ModelVertex {
+                    pos: (x, y, z).into(),
+                    normal: (nx, ny, nz).into(),
+                    tex_coord: (u, v).into(),
+                }
+            })
+            .collect::<Vec<ModelVertex>>();
+
+        MeshletMesh::new(gl, &vertices, &model_mesh.meshlets())
+    }
+
+    // Function to get the number of meshlets
+    pub fn meshlet_count(&self) -> usize {
+        self.meshlet_count
+    }
+
+    // Function to get the size of one vertex in bytes, the stride for vertex pulling
+    pub fn vertex_size(&self) -> usize {
+        self.vertex_size
+    }
+
+    // Function to draw the triangles left by the last cull with the currently used program,
+    // vertices are bound as shader storage buffer at binding and read at gl_VertexID like
+    // with Mesh::draw_pulled
+    pub fn draw(&self, vertex_binding: u32) {
+        self.vertices.bind_base(vertex_binding);
+        self.vao.bind();
+        validate_before_draw(&self.gl);
+        unsafe {
+            self.gl
+                .BindBuffer(gl::DRAW_INDIRECT_BUFFER, self.command.id());
+            self.gl.DrawElementsIndirect(
+                gl::TRIANGLES,      // mode
+                gl::UNSIGNED_INT,   // type of the indices
+                ::std::ptr::null(), // byte offset into the indirect buffer
+            );
+            self.gl.BindBuffer(gl::DRAW_INDIRECT_BUFFER, 0);
+        }
+        self.vao.unbind();
+    }
+}
+
+// Struct that represents the meshlet culling compute shader
+pub struct MeshletCuller {
+    // The gl context
+    gl: gl::Gl,
+    // Program compiled from shaders/meshlet_cull.comp
+    compute: ComputeProgram,
+}
+
+// Implementation of meshlet culler
+impl MeshletCuller {
+    // Function to create culler from the shaders/meshlet_cull compute shader
+    pub fn from_res(gl: &gl::Gl, res: &Resources) -> Result<MeshletCuller, Error> {
+        Ok(MeshletCuller {
+            gl: gl.clone(),
+            compute: ComputeProgram::from_res(gl, res, "shaders/meshlet_cull")?,
+        })
+    }
+
+    // Function to collect the triangles of meshlets visible from the camera into the index
+    // buffer of the mesh, model places the mesh in the world (rotation, translation and
+    // uniform scale, as the normal cones are tested in model space)
+    pub fn cull(&self, mesh: &MeshletMesh, camera: &Camera, model: &Mat4) {
+        let command = DrawCommand {
+            count: 0,
+            instance_count: 1,
+            first_index: 0,
+            base_vertex: 0,
+            base_instance: 0,
+        };
+        mesh.command.bind();
+        mesh.command.update_sub_data(0, &[command]);
+        mesh.command.unbind();
+
+        let model_view_projection = camera.projection_matrix() * camera.view_matrix() * *model;
+        let camera_position = model.inverse().transform_point3(camera.position());
+        let program = self.compute.program();
+        self.compute.set_used();
+        program.set_uniform_mat4("ModelViewProjection", &model_view_projection);
+        program.set_uniform_vec3("CameraPosition", camera_position);
+        program.set_uniform_1i("MeshletCount", mesh.meshlet_count as i32);
+
+        self.compute.bind_storage_buffer(0, &mesh.meshlets);
+        self.compute.bind_storage_buffer(1, &mesh.meshlet_vertices);
+        self.compute.bind_storage_buffer(2, &mesh.meshlet_triangles);
+        self.compute.bind_storage_buffer(3, &mesh.indices);
+        self.compute.bind_storage_buffer(4, &mesh.command);
+        let [group_size, _, _] = self.compute.work_group_size();
+        let groups = (mesh.meshlet_count as u32).div_ceil(group_size.max(1));
+        self.compute.dispatch(groups, 1, 1);
+
+        // Make the written indices and command visible to the draw
+        compute::vertex_barrier(&self.gl);
+    }
+}
+
+// Helper function to create a shader storage buffer filled with data
+fn storage_buffer<T>(gl: &gl::Gl, data: &[T]) -> ShaderStorageBuffer {
+    let buffer = ShaderStorageBuffer::new(gl);
+    buffer.bind();
+    buffer.static_draw_data(data);
+    buffer.unbind();
+    buffer
+}
+
+// Helper function to create a shader storage buffer with size bytes written by shaders
+fn storage_allocation(gl: &gl::Gl, size: usize) -> ShaderStorageBuffer {
+    let buffer = ShaderStorageBuffer::new(gl);
+    buffer.bind();
+    buffer.allocate(size, Usage::DynamicDraw);
+    buffer.unbind();
+    buffer
+}
@@ -1,5 +1,26 @@
 pub mod buffer;
+pub mod camera;
+pub mod compute;
 pub mod data;
+pub mod day_night;
+pub mod extensions;
+pub mod framebuffer;
+pub mod histogram;
+pub mod mesh;
+pub mod meshlet;
+pub mod noise;
+pub mod offscreen;
+pub mod per_draw;
+pub mod program_cache;
+pub mod queue;
+pub mod readback;
+pub mod robustness;
 mod shader;
+pub mod sky;
+pub mod state;
+pub mod streaming;
+pub mod texture;
+pub mod vertex;
 
-pub use self::shader::{Error, Program, Shader};
+pub use self::shader::{Error, Program, Shader, Specialization};
+pub use self::vertex::Vertex;
@@ -0,0 +1,247 @@
+// Import dependencies
+use gl;
+
+use crate::render::compute::{self, ComputeProgram, ImageAccess};
+use crate::render::texture::{InternalFormat, Texture2D};
+use crate::render::Error;
+use crate::resources::Resources;
+
+// Enum that represents a kind of gradient or cellular noise
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NoiseKind {
+    // Smooth gradient noise on a square grid
+    Perlin,
+    // Gradient noise on a triangle grid, fewer directional artifacts than perlin
+    Simplex,
+    // Distance to the closest random feature point, cell-like patterns
+    Worley,
+}
+
+// Implementation of noise kind
+impl NoiseKind {
+    // Function to get value of the Kind uniform of the noise compute shader
+    fn to_uniform(self) -> i32 {
+        match self {
+            NoiseKind::Perlin => 0,
+            NoiseKind::Simplex => 1,
+            NoiseKind::Worley => 2,
+        }
+    }
+}
+
+// Struct that represents parameters of fractal noise, the same parameters produce the same
+// texels on the cpu and the gpu (up to float precision)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NoiseParams {
+    pub kind: NoiseKind,
+    // Number of noise cells across the texture width in the first octave
+    pub frequency: f32,
+    // Number of summed layers, each adds finer detail
+    pub octaves: u32,
+    // Frequency multiplier between octaves
+    pub lacunarity: f32,
+    // Amplitude multiplier between octaves
+    pub gain: f32,
+    pub seed: u32,
+}
+
+// Implement default trait for noise params
+impl Default for NoiseParams {
+    fn default() -> Self {
+        NoiseParams {
+            kind: NoiseKind::Perlin,
+            frequency: 8.0,
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+            seed: 0,
+        }
+    }
+}
+
+// Function to generate width * height noise values in [0, 1] on the cpu, rows from bottom
+// to top as expected by texture uploads
+pub fn generate(width: u32, height: u32, params: &NoiseParams) -> Vec<f32> {
+    let mut values = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            values.push(texel(x, y, width, params));
+        }
+    }
+    values
+}
+
+// Function to get noise value in [0, 1] of a texel, cells are square so the texture height
+// does not stretch the pattern
+pub fn texel(x: u32, y: u32, width: u32, params: &NoiseParams) -> f32 {
+    let scale = params.frequency / width.max(1) as f32;
+    fractal((x as f32 + 0.5) * scale, (y as f32 + 0.5) * scale, params)
+}
+
+// Function to sum octaves of noise at a point, result is in [0, 1]
+pub fn fractal(x: f32, y: f32, params: &NoiseParams) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 1.0;
+    let mut total_amplitude = 0.0;
+    let mut frequency = 1.0;
+    for octave in 0..params.octaves.max(1) {
+        let seed = params.seed.wrapping_add(octave);
+        let value = match params.kind {
+            // Gradient noise is in [-1, 1]
+            NoiseKind::Perlin => perlin(x * frequency, y * frequency, seed) * 0.5 + 0.5,
+            NoiseKind::Simplex => simplex(x * frequency, y * frequency, seed) * 0.5 + 0.5,
+            NoiseKind::Worley => worley(x * frequency, y * frequency, seed),
+        };
+        sum += value * amplitude;
+        total_amplitude += amplitude;
+        amplitude *= params.gain;
+        frequency *= params.lacunarity;
+    }
+
+    (sum / total_amplitude).clamp(0.0, 1.0)
+}
+
+// Function to get perlin noise at a point, result is in [-1, 1]
+pub fn perlin(x: f32, y: f32, seed: u32) -> f32 {
+    let (cell_x, cell_y) = (x.floor(), y.floor());
+    let (fx, fy) = (x - cell_x, y - cell_y);
+    let (ix, iy) = (cell_x as i32, cell_y as i32);
+
+    let n00 = gradient_dot(hash(ix, iy, seed), fx, fy);
+    let n10 = gradient_dot(hash(ix + 1, iy, seed), fx - 1.0, fy);
+    let n01 = gradient_dot(hash(ix, iy + 1, seed), fx, fy - 1.0);
+    let n11 = gradient_dot(hash(ix + 1, iy + 1, seed), fx - 1.0, fy - 1.0);
+
+    let (u, v) = (fade(fx), fade(fy));
+    let bottom = n00 + (n10 - n00) * u;
+    let top = n01 + (n11 - n01) * u;
+    // Largest possible value of unit gradients is sqrt(0.5), scale it to 1
+    ((bottom + (top - bottom) * v) * ::std::f32::consts::SQRT_2).clamp(-1.0, 1.0)
+}
+
+// Function to get simplex noise at a point, result is in [-1, 1]
+pub fn simplex(x: f32, y: f32, seed: u32) -> f32 {
+    // Skew factors between the square and the triangle grid
+    const F2: f32 = 0.366_025_42; // (sqrt(3) - 1) / 2
+    const G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+
+    let s = (x + y) * F2;
+    let (cell_x, cell_y) = ((x + s).floor(), (y + s).floor());
+    let t = (cell_x + cell_y) * G2;
+    let (x0, y0) = (x - (cell_x - t), y - (cell_y - t));
+    let (ix, iy) = (cell_x as i32, cell_y as i32);
+
+    // Second corner depends on which triangle of the cell the point is in
+    let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+    let (x1, y1) = (x0 - i1 as f32 + G2, y0 - j1 as f32 + G2);
+    let (x2, y2) = (x0 - 1.0 + 2.0 * G2, y0 - 1.0 + 2.0 * G2);
+
+    let corner = |h: u32, x: f32, y: f32| {
+        let t = 0.5 - x * x - y * y;
+        if t <= 0.0 {
+            0.0
+        } else {
+            t * t * t * t * gradient_dot(h, x, y)
+        }
+    };
+    let n0 = corner(hash(ix, iy, seed), x0, y0);
+    let n1 = corner(hash(ix + i1, iy + j1, seed), x1, y1);
+    let n2 = corner(hash(ix + 1, iy + 1, seed), x2, y2);
+
+    (70.0 * (n0 + n1 + n2)).clamp(-1.0, 1.0)
+}
+
+// Function to get worley (cellular) noise at a point, the distance to the closest feature
+// point with one point per cell, result is in [0, 1]
+pub fn worley(x: f32, y: f32, seed: u32) -> f32 {
+    let (cell_x, cell_y) = (x.floor(), y.floor());
+    let (ix, iy) = (cell_x as i32, cell_y as i32);
+
+    let mut closest = f32::MAX;
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let h = hash(ix + dx, iy + dy, seed);
+            let feature_x = cell_x + dx as f32 + (h & 0xffff) as f32 / 65535.0;
+            let feature_y = cell_y + dy as f32 + (h >> 16) as f32 / 65535.0;
+            let (distance_x, distance_y) = (feature_x - x, feature_y - y);
+            closest = closest.min(distance_x * distance_x + distance_y * distance_y);
+        }
+    }
+
+    closest.sqrt().min(1.0)
+}
+
+// Struct that represents the noise compute shader, generates noise straight into textures
+pub struct NoiseGenerator {
+    // The gl context
+    gl: gl::Gl,
+    // Program compiled from shaders/noise.comp
+    compute: ComputeProgram,
+}
+
+// Implementation of noise generator
+impl NoiseGenerator {
+    // Function to create noise generator from the shaders/noise compute shader
+    pub fn from_res(gl: &gl::Gl, res: &Resources) -> Result<NoiseGenerator, Error> {
+        Ok(NoiseGenerator {
+            gl: gl.clone(),
+            compute: ComputeProgram::from_res(gl, res, "shaders/noise")?,
+        })
+    }
+
+    // Function to fill an R32F texture with noise values in [0, 1]
+    pub fn generate(&self, texture: &Texture2D, params: &NoiseParams) {
+        assert_eq!(texture.format(), InternalFormat::R32F);
+
+        let program = self.compute.program();
+        self.compute.set_used();
+        program.set_uniform_1i("Kind", params.kind.to_uniform());
+        program.set_uniform_1f("Frequency", params.frequency);
+        program.set_uniform_1i("Octaves", params.octaves.max(1) as i32);
+        program.set_uniform_1f("Lacunarity", params.lacunarity);
+        program.set_uniform_1f("Gain", params.gain);
+        // The shader reads the bits back as uint
+        program.set_uniform_1i("Seed", params.seed as i32);
+
+        self.compute.bind_image(0, texture, ImageAccess::WriteOnly);
+        let (width, height) = (texture.width(), texture.height());
+        self.compute.dispatch_for(width, height, 1);
+
+        // Make the written texels visible to following texture reads
+        compute::texture_fetch_barrier(&self.gl);
+    }
+}
+
+// Helper function to hash integer cell coordinates, shaders/noise.comp uses the same hash
+fn hash(x: i32, y: i32, seed: u32) -> u32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343)
+        ^ (y as u32).wrapping_mul(0xd816_3841)
+        ^ seed.wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^= h >> 16;
+    h
+}
+
+// Helper function to take the dot product of one of 8 unit gradients picked by the hash
+// with the offset from the grid corner
+fn gradient_dot(h: u32, x: f32, y: f32) -> f32 {
+    const DIAGONAL: f32 = ::std::f32::consts::FRAC_1_SQRT_2;
+    match h & 7 {
+        0 => x,
+        1 => -x,
+        2 => y,
+        3 => -y,
+        4 => (x + y) * DIAGONAL,
+        5 => (-x + y) * DIAGONAL,
+        6 => (x - y) * DIAGONAL,
+        _ => (-x - y) * DIAGONAL,
+    }
+}
+
+// Helper function to smooth interpolation weights, 6t^5 - 15t^4 + 10t^3
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
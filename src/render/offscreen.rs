@@ -0,0 +1,113 @@
+// Import dependencies
+use gl;
+
+use crate::render::framebuffer::{Attachment, Error, Framebuffer};
+use crate::render::state::StateScope;
+use crate::render::texture::{InternalFormat, Texture2D};
+
+// Struct that represents a color texture and a depth buffer to render a pass into, e.g. the
+// scene before post-processing. It is usually sized to the window and resized with it.
+pub struct OffscreenTarget {
+    // The gl context
+    gl: gl::Gl,
+    // Framebuffer with the color texture and the depth renderbuffer
+    framebuffer: Framebuffer,
+}
+
+// Implementation of offscreen target
+impl OffscreenTarget {
+    // Constructor for a target with an RGBA8 color texture
+    pub fn new(gl: &gl::Gl, width: u32, height: u32) -> Result<OffscreenTarget, Error> {
+        OffscreenTarget::with_format(gl, width, height, InternalFormat::Rgba8)
+    }
+
+    // Constructor for a target with a color texture of the given format, e.g. Rgba16F for
+    // HDR colors
+    pub fn with_format(
+        gl: &gl::Gl,
+        width: u32,
+        height: u32,
+        format: InternalFormat,
+    ) -> Result<OffscreenTarget, Error> {
+        let mut framebuffer = Framebuffer::new(gl, width, height);
+        framebuffer.attach_texture(Attachment::Color(0), format);
+        framebuffer.attach_renderbuffer(Attachment::DepthStencil, InternalFormat::Depth24Stencil8);
+        framebuffer.check()?;
+
+        Ok(OffscreenTarget {
+            gl: gl.clone(),
+            framebuffer,
+        })
+    }
+
+    // Function to start rendering into the target, binds it and sets the viewport to its
+    // size until the returned pass is ended or dropped
+    pub fn begin(&self) -> OffscreenPass<'_> {
+        let mut state = StateScope::new(&self.gl);
+        state.set_framebuffer(self.framebuffer.id());
+        state.set_viewport(0, 0, self.width() as i32, self.height() as i32);
+
+        OffscreenPass {
+            target: self,
+            _state: state,
+        }
+    }
+
+    // Function to recreate color texture and depth buffer at a new size, e.g. after the
+    // window was resized
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        self.framebuffer.resize(width, height)
+    }
+
+    // Function to get the color texture, e.g. to sample it in the next pass
+    pub fn color_texture(&self) -> &Texture2D {
+        self.framebuffer
+            .texture(Attachment::Color(0))
+            .expect("offscreen target always has a color texture")
+    }
+
+    // Function to bind the color texture to a texture unit for a subsequent pass
+    pub fn bind_color_at(&self, unit: u32) {
+        self.color_texture().bind_at(unit);
+    }
+
+    // Function to get the underlying framebuffer
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    // Function to get width of the target in pixels
+    pub fn width(&self) -> u32 {
+        self.framebuffer.width()
+    }
+
+    // Function to get height of the target in pixels
+    pub fn height(&self) -> u32 {
+        self.framebuffer.height()
+    }
+}
+
+// Struct that represents a pass rendering into an offscreen target, the previous framebuffer
+// and viewport are restored when it ends
+pub struct OffscreenPass<'a> {
+    // The target rendered into
+    target: &'a OffscreenTarget,
+    // Saved framebuffer binding and viewport
+    _state: StateScope,
+}
+
+// Implementation of offscreen pass
+impl<'a> OffscreenPass<'a> {
+    // Function to clear the color texture to a color, depth to the far plane and stencil to
+    // 0, without changing the clear color used for the window
+    pub fn clear(&self, color: [f32; 4]) {
+        let gl = &self.target.gl;
+        unsafe {
+            gl.ClearBufferfv(gl::COLOR, 0, color.as_ptr());
+            gl.ClearBufferfi(gl::DEPTH_STENCIL, 0, 1.0, 0);
+        }
+    }
+
+    // Function to end the pass, same as dropping it
+    pub fn end(self) {}
+}
@@ -0,0 +1,133 @@
+// Import dependencies
+use gl;
+
+use crate::math::Mat4;
+use crate::render::buffer::UniformBuffer;
+
+// Uniform block binding point used for per-draw data, shaders declare the block as
+// `layout (std140) uniform PerDraw { mat4 Model; uint MaterialIndex; };`
+pub const PER_DRAW_BINDING: u32 = 0;
+
+// Struct that represents per-object data laid out to match the std140 PerDraw block
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct PerDrawData {
+    pub model: Mat4,
+    pub material_index: u32,
+    // std140 rounds block size up to the alignment of vec4
+    pub _padding: [u32; 3],
+}
+
+// Implementation of per-draw data
+impl PerDrawData {
+    // Function which creates per-draw data
+    pub fn new(model: Mat4, material_index: u32) -> Self {
+        Self {
+            model,
+            material_index,
+            _padding: [0; 3],
+        }
+    }
+}
+
+// Struct that represents a uniform buffer split into slots which are written in a ring,
+// each draw writes its data into the next slot and binds just that range
+pub struct UniformRing<T> {
+    // The gl context
+    gl: gl::Gl,
+    // The uniform buffer holding all slots
+    buffer: UniformBuffer,
+    // Distance in bytes between slots, respecting uniform buffer offset alignment
+    stride: usize,
+    // Number of slots
+    capacity: usize,
+    // Next slot to write
+    head: usize,
+    // The PhantomData marker
+    _marker: ::std::marker::PhantomData<T>,
+}
+
+// Implementation of uniform ring
+impl<T> UniformRing<T>
+where
+    T: Copy,
+{
+    // Constructor for a ring with the given number of slots, which should cover the number
+    // of draws in flight (e.g. draws per frame times frames buffered by the driver)
+    pub fn new(gl: &gl::Gl, capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        // Get required alignment of bound ranges
+        let mut alignment: gl::types::GLint = 0;
+        unsafe {
+            gl.GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut alignment);
+        }
+        let alignment = (alignment as usize).max(1);
+        let stride = ::std::mem::size_of::<T>().div_ceil(alignment) * alignment;
+
+        // Allocate storage for all slots
+        let buffer = UniformBuffer::new(gl);
+        buffer.bind();
+        unsafe {
+            gl.BufferData(
+                gl::UNIFORM_BUFFER,                           // target
+                (stride * capacity) as gl::types::GLsizeiptr, // size of data in bytes
+                ::std::ptr::null(),                           // no data, storage only
+                gl::STREAM_DRAW,                              // usage
+            );
+        }
+        buffer.unbind();
+
+        Self {
+            gl: gl.clone(),
+            buffer,
+            stride,
+            capacity,
+            head: 0,
+            _marker: ::std::marker::PhantomData,
+        }
+    }
+
+    // Function to write data into the next slot, returns its byte offset
+    pub fn push(&mut self, data: &T) -> usize {
+        let offset = self.head * self.stride;
+        self.head = (self.head + 1) % self.capacity;
+
+        self.buffer.bind();
+        unsafe {
+            self.gl.BufferSubData(
+                gl::UNIFORM_BUFFER,
+                offset as gl::types::GLintptr,
+                ::std::mem::size_of::<T>() as gl::types::GLsizeiptr,
+                data as *const T as *const gl::types::GLvoid,
+            );
+        }
+        self.buffer.unbind();
+
+        offset
+    }
+
+    // Function to bind the slot at the given byte offset to a uniform block binding point
+    pub fn bind(&self, binding: u32, offset: usize) {
+        unsafe {
+            self.gl.BindBufferRange(
+                gl::UNIFORM_BUFFER,
+                binding,
+                self.buffer.id(),
+                offset as gl::types::GLintptr,
+                ::std::mem::size_of::<T>() as gl::types::GLsizeiptr,
+            );
+        }
+    }
+
+    // Function to write data into the next slot and bind it, replacing per-draw uniform calls
+    pub fn push_and_bind(&mut self, binding: u32, data: &T) {
+        let offset = self.push(data);
+        self.bind(binding, offset);
+    }
+
+    // Function to get the number of slots
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
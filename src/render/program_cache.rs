@@ -0,0 +1,264 @@
+// Import dependencies
+use gl;
+use std::ffi::CStr;
+use std::fs;
+use std::hash::Hasher;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Struct that represents the key of a stored program binary
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CacheKey {
+    // Hash of the program name, binaries of a program replace each other
+    name: u64,
+    // Hash of the driver and everything the binary depends on
+    contents: u64,
+}
+
+// Struct that represents a directory of linked program binaries, which lets drivers skip
+// compiling and linking shaders that did not change since the last run
+pub struct ProgramCache {
+    // Directory holding the binary of the last version of each program
+    dir: PathBuf,
+    // Hash of vendor, renderer and version strings, binaries of another driver are rejected
+    // by glProgramBinary anyway, keying by driver avoids trying them
+    driver_hash: u64,
+}
+
+// Implementation of program cache
+impl ProgramCache {
+    // Constructor for a cache stored in the given directory, created on first store
+    pub fn new(gl: &gl::Gl, dir: &Path) -> ProgramCache {
+        let mut hasher = FnvHasher::new();
+        for name in [gl::VENDOR, gl::RENDERER, gl::VERSION] {
+            let value = unsafe { gl.GetString(name) };
+            if !value.is_null() {
+                hasher.write_part(unsafe { CStr::from_ptr(value as *const _) }.to_bytes());
+            }
+        }
+
+        ProgramCache {
+            dir: dir.into(),
+            driver_hash: hasher.finish(),
+        }
+    }
+
+    // Constructor for a cache stored in a directory next to the executable
+    pub fn from_relative_exe_path(gl: &gl::Gl, rel_path: &Path) -> io::Result<ProgramCache> {
+        let exe_file_name = ::std::env::current_exe()?;
+        let exe_path = exe_file_name
+            .parent()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "executable has no parent"))?;
+
+        Ok(ProgramCache::new(gl, &exe_path.join(rel_path)))
+    }
+
+    // Function to compute cache key of a program from everything its binary depends on,
+    // usually stage resource names with their contents. Parts are hashed with their length,
+    // so moving bytes between parts changes the key.
+    pub fn key(&self, name: &str, parts: &[&[u8]]) -> CacheKey {
+        let mut hasher = FnvHasher::new();
+        hasher.write_u64(self.driver_hash);
+        for part in parts {
+            hasher.write_part(part);
+        }
+
+        CacheKey {
+            name: fnv(name.as_bytes()),
+            contents: hasher.finish(),
+        }
+    }
+
+    // Function to create a linked program object from the binary stored for the key, None
+    // if there is no binary or the driver rejected it
+    pub fn load(&self, gl: &gl::Gl, key: CacheKey) -> Option<gl::types::GLuint> {
+        let data = fs::read(self.path(key)).ok()?;
+        if data.len() < 4 {
+            return None;
+        }
+        let (format, binary) = data.split_at(4);
+        let format = u32::from_le_bytes([format[0], format[1], format[2], format[3]]);
+
+        let id = unsafe { gl.CreateProgram() };
+        let mut success: gl::types::GLint = 0;
+        unsafe {
+            gl.ProgramBinary(
+                id,
+                format,
+                binary.as_ptr() as *const gl::types::GLvoid,
+                binary.len() as gl::types::GLsizei,
+            );
+            gl.GetProgramiv(id, gl::LINK_STATUS, &mut success);
+        }
+
+        if success == 0 {
+            unsafe {
+                gl.DeleteProgram(id);
+            }
+            return None;
+        }
+
+        Some(id)
+    }
+
+    // Function to store binary of a linked program under the key, replacing binaries stored
+    // for other versions of the program
+    pub fn store(&self, gl: &gl::Gl, key: CacheKey, program: gl::types::GLuint) -> io::Result<()> {
+        let mut len: gl::types::GLint = 0;
+        unsafe {
+            gl.GetProgramiv(program, gl::PROGRAM_BINARY_LENGTH, &mut len);
+        }
+        if len <= 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "driver does not provide program binaries",
+            ));
+        }
+
+        let mut format: gl::types::GLenum = 0;
+        let mut binary: Vec<u8> = vec![0; len as usize];
+        unsafe {
+            gl.GetProgramBinary(
+                program,
+                len,
+                ::std::ptr::null_mut(),
+                &mut format,
+                binary.as_mut_ptr() as *mut gl::types::GLvoid,
+            );
+        }
+
+        let mut data = Vec::with_capacity(4 + binary.len());
+        data.extend_from_slice(&format.to_le_bytes());
+        data.extend_from_slice(&binary);
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(key), data)?;
+        self.prune(key)
+    }
+
+    // Function to remove all stored binaries
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    // Helper function to remove binaries of the program other than the one of the key, e.g.
+    // left behind by edited sources or another driver
+    fn prune(&self, key: CacheKey) -> io::Result<()> {
+        let prefix = format!("{:016x}-", key.name);
+        let current = self.path(key);
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let stale = path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .is_some_and(|file_name| file_name.starts_with(&prefix));
+            if stale && path != current {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Helper function to get path of the binary stored for the key
+    fn path(&self, key: CacheKey) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}-{:016x}.bin", key.name, key.contents))
+    }
+}
+
+// Struct that represents a 64 bit FNV-1a hasher, unlike DefaultHasher its output is fixed
+// across Rust releases so keys of stored binaries stay valid after a toolchain update
+struct FnvHasher(u64);
+
+// Implementation of FNV-1a hasher
+impl FnvHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    // Constructor for a hasher starting at the offset basis
+    fn new() -> FnvHasher {
+        FnvHasher(FnvHasher::OFFSET_BASIS)
+    }
+
+    // Function to hash the length and the bytes of a part of the key
+    fn write_part(&mut self, bytes: &[u8]) {
+        self.write_u64(bytes.len() as u64);
+        self.write(bytes);
+    }
+}
+
+// Implementation of Hasher trait for FNV-1a hasher
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(FnvHasher::PRIME);
+        }
+    }
+
+    // Integers are hashed as little endian bytes on every platform
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+// Function to hash bytes with a new FNV-1a hasher
+fn fnv(bytes: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::new();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv_matches_reference_values() {
+        assert_eq!(fnv(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    // Function to create a cache without a context, keys then only depend on the parts
+    fn cache(dir: PathBuf) -> ProgramCache {
+        ProgramCache {
+            dir,
+            driver_hash: 0,
+        }
+    }
+
+    #[test]
+    fn key_separates_parts() {
+        let cache = cache(PathBuf::new());
+        let key = cache.key("shaders/triangle", &[b"ab", b"c"]);
+        assert_eq!(key, cache.key("shaders/triangle", &[b"ab", b"c"]));
+        assert_ne!(key, cache.key("shaders/triangle", &[b"a", b"bc"]));
+        assert_ne!(key.name, cache.key("shaders/quad", &[b"ab", b"c"]).name);
+    }
+
+    #[test]
+    fn store_prunes_other_versions() {
+        let dir = ::std::env::temp_dir().join(format!("program_cache_{}", ::std::process::id()));
+        let cache = cache(dir.clone());
+        let old = cache.key("shaders/triangle", &[b"old"]);
+        let new = cache.key("shaders/triangle", &[b"new"]);
+        let other = cache.key("shaders/quad", &[b"old"]);
+        fs::create_dir_all(&dir).unwrap();
+        for key in [old, new, other] {
+            fs::write(cache.path(key), b"binary").unwrap();
+        }
+
+        cache.prune(new).unwrap();
+        let exists = [old, new, other].map(|key| cache.path(key).exists());
+        cache.clear().unwrap();
+        assert_eq!(exists, [false, true, true]);
+    }
+}
@@ -0,0 +1,123 @@
+// Bit layout of the sort key, from most to least significant:
+// layer (8 bits) | program (16 bits) | texture (16 bits) | depth (24 bits)
+const LAYER_SHIFT: u32 = 56;
+const PROGRAM_SHIFT: u32 = 40;
+const TEXTURE_SHIFT: u32 = 24;
+const DEPTH_BITS: u32 = 24;
+const DEPTH_MAX: u64 = (1 << DEPTH_BITS) - 1;
+
+// Enum that represents a render queue layer, layers are drawn in declaration order
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    Background = 0,
+    Opaque = 1,
+    Transparent = 2,
+    Overlay = 3,
+}
+
+// Struct that represents a 64-bit key commands are sorted by
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey(pub u64);
+
+// Implementation of sort key
+impl SortKey {
+    // Function to compose sort key, program and texture ids are truncated to 16 bits and
+    // depth is expected in 0..1 range (e.g. normalized view depth). Transparent commands
+    // get their depth inverted so they are drawn back to front.
+    pub fn new(
+        layer: Layer,
+        program: gl::types::GLuint,
+        texture: gl::types::GLuint,
+        depth: f32,
+    ) -> SortKey {
+        let mut depth = (depth.clamp(0.0, 1.0) * DEPTH_MAX as f32) as u64;
+        if layer == Layer::Transparent {
+            depth = DEPTH_MAX - depth;
+        }
+
+        SortKey(
+            (layer as u64) << LAYER_SHIFT
+                | (program as u64 & 0xFFFF) << PROGRAM_SHIFT
+                | (texture as u64 & 0xFFFF) << TEXTURE_SHIFT
+                | depth,
+        )
+    }
+
+    // Function to get the layer stored in the key
+    pub fn layer(&self) -> Layer {
+        match self.0 >> LAYER_SHIFT {
+            0 => Layer::Background,
+            1 => Layer::Opaque,
+            2 => Layer::Transparent,
+            _ => Layer::Overlay,
+        }
+    }
+
+    // Function to get the (truncated) program id stored in the key
+    pub fn program(&self) -> u16 {
+        (self.0 >> PROGRAM_SHIFT) as u16
+    }
+
+    // Function to get the (truncated) texture id stored in the key
+    pub fn texture(&self) -> u16 {
+        (self.0 >> TEXTURE_SHIFT) as u16
+    }
+}
+
+// Struct that represents a list of commands drawn in sort key order
+pub struct RenderQueue<T> {
+    commands: Vec<(SortKey, T)>,
+}
+
+// Implementation of render queue
+impl<T> RenderQueue<T> {
+    // Constructor for an empty render queue
+    pub fn new() -> RenderQueue<T> {
+        RenderQueue {
+            commands: Vec::new(),
+        }
+    }
+
+    // Function to add command to the queue
+    pub fn push(&mut self, key: SortKey, command: T) {
+        self.commands.push((key, command));
+    }
+
+    // Function to sort commands by key, commands with equal keys keep submission order
+    pub fn sort(&mut self) {
+        self.commands.sort_by_key(|&(key, _)| key);
+    }
+
+    // Function to iterate over commands in current order
+    pub fn iter(&self) -> impl Iterator<Item = &(SortKey, T)> {
+        self.commands.iter()
+    }
+
+    // Function to sort and take all commands out of the queue, leaving it empty for next frame
+    pub fn drain_sorted(&mut self) -> std::vec::Drain<'_, (SortKey, T)> {
+        self.sort();
+        self.commands.drain(..)
+    }
+
+    // Function to get the number of queued commands
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    // Function to check if the queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    // Function to remove all commands
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+// Implement default trait for render queue
+impl<T> Default for RenderQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
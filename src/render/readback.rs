@@ -0,0 +1,146 @@
+// Import dependencies
+use gl;
+use image::RgbaImage;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::image_util;
+use crate::render::buffer::{PixelPackBuffer, Usage};
+
+// Struct that represents a readback copied into a pixel buffer and waiting for the gpu
+struct PendingReadback {
+    // Pixel buffer the pixels are copied into
+    buffer: PixelPackBuffer,
+    // Fence signaled once the copy has finished
+    fence: gl::types::GLsync,
+    // Size of the read rectangle in pixels
+    width: u32,
+    height: u32,
+    // Channel the finished image is delivered to
+    sender: Sender<RgbaImage>,
+}
+
+// Struct that represents asynchronous pixel readbacks, e.g. for screenshots. Pixels are
+// copied into pixel buffers on the gpu and only read by the cpu once a fence says the copy
+// has finished, usually a few frames later, so reading never stalls the frame.
+pub struct ReadbackManager {
+    // The gl context
+    gl: gl::Gl,
+    // Readbacks in the order they were requested
+    pending: Vec<PendingReadback>,
+    // Pixel buffers of finished readbacks, reused by the next requests
+    free_buffers: Vec<PixelPackBuffer>,
+}
+
+// Implementation of readback manager
+impl ReadbackManager {
+    // Constructor for a manager without readbacks
+    pub fn new(gl: &gl::Gl) -> ReadbackManager {
+        ReadbackManager {
+            gl: gl.clone(),
+            pending: Vec::new(),
+            free_buffers: Vec::new(),
+        }
+    }
+
+    // Function to schedule a readback of a rectangle (origin at the bottom left) of the
+    // current read framebuffer, e.g. the back buffer after drawing a frame. The image arrives
+    // on the returned channel top row first once poll finds the copy finished.
+    pub fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> Receiver<RgbaImage> {
+        let (sender, receiver) = mpsc::channel();
+        let buffer = self
+            .free_buffers
+            .pop()
+            .unwrap_or_else(|| PixelPackBuffer::new(&self.gl));
+
+        buffer.bind();
+        buffer.allocate(image_util::buffer_len(width, height, 4), Usage::StreamRead);
+        let fence = unsafe {
+            // Rows of RGBA8 pixels are always 4 byte aligned
+            self.gl.PixelStorei(gl::PACK_ALIGNMENT, 4);
+            self.gl.ReadPixels(
+                x,
+                y,
+                width as gl::types::GLsizei,
+                height as gl::types::GLsizei,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null_mut(), // offset into the pixel pack buffer
+            );
+            self.gl.FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0)
+        };
+        buffer.unbind();
+
+        self.pending.push(PendingReadback {
+            buffer,
+            fence,
+            width,
+            height,
+            sender,
+        });
+        receiver
+    }
+
+    // Function to deliver every finished readback, call it once per frame
+    pub fn poll(&mut self) {
+        let mut index = 0;
+        while index < self.pending.len() {
+            if self.is_signaled(self.pending[index].fence) {
+                let readback = self.pending.remove(index);
+                self.finish(readback);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    // Function to get the number of readbacks still waiting for the gpu
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    // Function to check a fence without waiting for it
+    fn is_signaled(&self, fence: gl::types::GLsync) -> bool {
+        let mut status: gl::types::GLint = 0;
+        unsafe {
+            self.gl.GetSynciv(
+                fence,
+                gl::SYNC_STATUS,
+                1,                    // size of the values array
+                std::ptr::null_mut(), // length of the result is not needed
+                &mut status,
+            );
+        }
+        status as gl::types::GLenum == gl::SIGNALED
+    }
+
+    // Function to read the pixels of a finished readback and send them to the receiver
+    fn finish(&mut self, readback: PendingReadback) {
+        unsafe {
+            self.gl.DeleteSync(readback.fence);
+        }
+
+        let mut pixels = vec![0u8; image_util::buffer_len(readback.width, readback.height, 4)];
+        readback.buffer.bind();
+        readback.buffer.get_sub_data(0, &mut pixels);
+        readback.buffer.unbind();
+        if let Some(mut image) = RgbaImage::from_raw(readback.width, readback.height, pixels) {
+            // OpenGL returns the bottom row first
+            image_util::flip_vertical(&mut image);
+            // The receiver may have been dropped, nobody wants the image then
+            let _ = readback.sender.send(image);
+        }
+
+        self.free_buffers.push(readback.buffer);
+    }
+}
+
+// Drop trait implementation for readback manager
+impl Drop for ReadbackManager {
+    fn drop(&mut self) {
+        for readback in self.pending.iter() {
+            unsafe {
+                self.gl.DeleteSync(readback.fence);
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// Import dependencies
+use gl;
+
+// Enum that represents the graphics reset status of a context
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResetStatus {
+    // Context is usable
+    NoError,
+    // Reset was caused by this context
+    Guilty,
+    // Reset was caused by another context
+    Innocent,
+    // Reset cause is unknown
+    Unknown,
+}
+
+// Implementation of reset status
+impl ResetStatus {
+    // Function to check if the context was lost, in which case all its objects are gone and
+    // it has to be recreated
+    pub fn is_lost(self) -> bool {
+        self != ResetStatus::NoError
+    }
+}
+
+// Function to query the graphics reset status (requires Extensions::has_robustness), only
+// reports resets for contexts created with robust access and lose-context-on-reset
+// notification (see is_robust)
+pub fn reset_status(gl: &gl::Gl) -> ResetStatus {
+    match unsafe { gl.GetGraphicsResetStatus() } {
+        gl::NO_ERROR => ResetStatus::NoError,
+        gl::GUILTY_CONTEXT_RESET => ResetStatus::Guilty,
+        gl::INNOCENT_CONTEXT_RESET => ResetStatus::Innocent,
+        _ => ResetStatus::Unknown,
+    }
+}
+
+// Function to check if the current context has robust buffer access and is notified about
+// resets, i.e. whether reset_status can detect a lost context
+pub fn is_robust(gl: &gl::Gl) -> bool {
+    let mut flags: gl::types::GLint = 0;
+    let mut strategy: gl::types::GLint = 0;
+    unsafe {
+        gl.GetIntegerv(gl::CONTEXT_FLAGS, &mut flags);
+        gl.GetIntegerv(gl::RESET_NOTIFICATION_STRATEGY, &mut strategy);
+    }
+
+    flags as gl::types::GLenum & gl::CONTEXT_FLAG_ROBUST_ACCESS_BIT != 0
+        && strategy as gl::types::GLenum == gl::LOSE_CONTEXT_ON_RESET
+}
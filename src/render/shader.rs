@@ -1,6 +1,10 @@
 // Import namespace to avoid repeating `std::ffi` everywhere
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::ffi::{CStr, CString};
 
+use crate::math::{Mat4, Vec3};
+use crate::render::program_cache::ProgramCache;
 use crate::resources::Resources;
 
 // Enum which holds all the error's that can occur
@@ -15,6 +19,10 @@ pub enum Error {
     CanNotDetermineShaderTypeForResource {
         name: String,
     },
+    #[fail(display = "No shader stages found for program {}", name)]
+    NoShaderStagesForResource {
+        name: String,
+    },
     #[fail(display = "Failed to compile shader {}: {}", name, message)]
     CompileError {
         name: String,
@@ -27,36 +35,293 @@ pub enum Error {
     },
 }
 
+// Shader stages by resource file extension, in pipeline order
+const SHADER_EXT: [(&str, gl::types::GLenum); 5] = [
+    (".vert", gl::VERTEX_SHADER),
+    (".tesc", gl::TESS_CONTROL_SHADER),
+    (".tese", gl::TESS_EVALUATION_SHADER),
+    (".geom", gl::GEOMETRY_SHADER),
+    (".frag", gl::FRAGMENT_SHADER),
+];
+
+// Compute stage extension, kept apart from graphics stages as it is linked on its own
+const COMPUTE_EXT: (&str, gl::types::GLenum) = (".comp", gl::COMPUTE_SHADER);
+
+// Extension of precompiled SPIR-V shaders, appended to the stage extension
+// (e.g. triangle.vert.spv), which are preferred over GLSL sources when present
+const SPIRV_EXT: &str = ".spv";
+
+thread_local! {
+    // Pipeline states (program, vertex array, draw framebuffer) already validated before a
+    // draw in debug builds
+    static VALIDATED_PIPELINES: RefCell<HashSet<[gl::types::GLuint; 3]>> =
+        RefCell::new(HashSet::new());
+}
+
+// Struct that holds whether SPIR-V shaders may be used, and the entry point and
+// specialization constants used when loading them, GLSL shaders ignore it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Specialization {
+    // Whether SPIR-V binaries are preferred over GLSL sources (see Extensions::has_spirv)
+    spirv_supported: bool,
+    entry_point: String,
+    // Constant ids (layout (constant_id = N)) and their values as raw 32-bit words
+    constants: Vec<(u32, u32)>,
+}
+
+// Implementation of specialization
+impl Specialization {
+    // Constructor for "main" entry point without constants, GLSL sources are used unless
+    // SPIR-V support is set
+    pub fn new() -> Specialization {
+        Specialization {
+            spirv_supported: false,
+            entry_point: "main".into(),
+            constants: Vec::new(),
+        }
+    }
+
+    // Function to set whether the context can load SPIR-V binaries, usually from
+    // Extensions::has_spirv
+    pub fn spirv_supported(mut self, supported: bool) -> Specialization {
+        self.spirv_supported = supported;
+        self
+    }
+
+    // Function to set the entry point
+    pub fn entry_point(mut self, entry_point: &str) -> Specialization {
+        self.entry_point = entry_point.into();
+        self
+    }
+
+    // Function to set an integer specialization constant
+    pub fn constant_u32(mut self, id: u32, value: u32) -> Specialization {
+        self.constants.retain(|&(constant_id, _)| constant_id != id);
+        self.constants.push((id, value));
+        self
+    }
+
+    // Function to set a signed integer specialization constant
+    pub fn constant_i32(self, id: u32, value: i32) -> Specialization {
+        self.constant_u32(id, value as u32)
+    }
+
+    // Function to set a float specialization constant
+    pub fn constant_f32(self, id: u32, value: f32) -> Specialization {
+        self.constant_u32(id, value.to_bits())
+    }
+
+    // Function to set a bool specialization constant
+    pub fn constant_bool(self, id: u32, value: bool) -> Specialization {
+        self.constant_u32(id, value as u32)
+    }
+
+    // Helper function to serialize the specialization for program cache keys
+    fn key_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.spirv_supported as u8];
+        bytes.extend_from_slice(&(self.entry_point.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(self.entry_point.as_bytes());
+        for &(id, value) in &self.constants {
+            bytes.extend_from_slice(&id.to_le_bytes());
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+// Implement default trait for specialization
+impl Default for Specialization {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Newtype wrapper for program
 pub struct Program {
     gl: gl::Gl,
     id: gl::types::GLuint,
+    // Cache of uniform locations looked up by name
+    uniform_locations: RefCell<HashMap<String, gl::types::GLint>>,
+    // Resource name the program was created from, if any
+    name: Option<String>,
+    // Shader resources and their versions at the time they were loaded
+    source_versions: Vec<(String, u64)>,
+    // Specialization the program was created with, reused on reload
+    specialization: Specialization,
 }
 
 // Implementation of program
 impl Program {
-    // Function to create program from resource
+    // Function to create program from resource, linking every stage found for the base
+    // name (e.g. triangle.vert, triangle.geom and triangle.frag for "shaders/triangle")
     pub fn from_res(gl: &gl::Gl, res: &Resources, name: &str) -> Result<Program, Error> {
-        // Get possible file extensions
-        const POSSIBLE_EXT: [&str; 2] = [".vert", ".frag"];
+        Program::from_res_specialized(gl, res, name, &Specialization::new())
+    }
+
+    // Function to create program from resource like from_res, SPIR-V stages are specialized
+    // with the given entry point and constants
+    pub fn from_res_specialized(
+        gl: &gl::Gl,
+        res: &Resources,
+        name: &str,
+        specialization: &Specialization,
+    ) -> Result<Program, Error> {
+        Program::from_res_with_cache(gl, res, name, specialization, None)
+    }
+
+    // Function to create program from resource like from_res_specialized, reusing the
+    // linked binary stored in the cache when sources did not change, and storing it otherwise
+    pub fn from_res_cached(
+        gl: &gl::Gl,
+        res: &Resources,
+        name: &str,
+        specialization: &Specialization,
+        cache: &ProgramCache,
+    ) -> Result<Program, Error> {
+        Program::from_res_with_cache(gl, res, name, specialization, Some(cache))
+    }
 
-        // Get possible resource names
-        let resource_names = POSSIBLE_EXT
+    // Helper function to create program from resource, optionally through a binary cache
+    fn from_res_with_cache(
+        gl: &gl::Gl,
+        res: &Resources,
+        name: &str,
+        specialization: &Specialization,
+        cache: Option<&ProgramCache>,
+    ) -> Result<Program, Error> {
+        // Get possible resource names, SPIR-V binaries first
+        let resource_names = SHADER_EXT
             .iter()
-            .map(|file_extension| format!("{}{}", name, file_extension))
+            .flat_map(|&(file_extension, _)| {
+                [
+                    format!("{}{}{}", name, file_extension, SPIRV_EXT),
+                    format!("{}{}", name, file_extension),
+                ]
+            })
             .collect::<Vec<String>>();
 
-        // Get possible shader types
-        let shaders = resource_names
+        // Remember versions of the sources before loading them, missing stages are included
+        // so that adding one triggers a reload
+        let source_versions = resource_names
             .iter()
-            .map(|resource_name| Shader::from_res(gl, res, resource_name))
-            .collect::<Result<Vec<Shader>, Error>>()?;
+            .map(|resource_name| (resource_name.clone(), res.resource_version(resource_name)))
+            .collect::<Vec<(String, u64)>>();
+
+        // Get existing stages, preferring binaries when SPIR-V is supported
+        let stage_names = select_stages(
+            &resource_names,
+            |resource_name| res.exists(resource_name),
+            specialization.spirv_supported,
+        );
+        if stage_names.is_empty() {
+            return Err(Error::NoShaderStagesForResource { name: name.into() });
+        }
 
-        // Create program from shaders
-        Program::from_shaders(gl, &shaders[..]).map_err(|message| Error::LinkError {
-            name: name.into(),
-            message,
-        })
+        // Compute cache key from stage names, contents and specialization
+        let cache_key = match cache {
+            Some(cache) => {
+                let stages = stage_names
+                    .iter()
+                    .map(|resource_name| {
+                        res.load_bytes(resource_name)
+                            .map(|bytes| (resource_name.as_str(), bytes))
+                            .map_err(|e| Error::ResourceLoad {
+                                name: resource_name.to_string(),
+                                inner: e,
+                            })
+                    })
+                    .collect::<Result<Vec<(&str, Vec<u8>)>, Error>>()?;
+                let specialization = specialization.key_bytes();
+                let parts = stages
+                    .iter()
+                    .flat_map(|(resource_name, bytes)| [resource_name.as_bytes(), &bytes[..]])
+                    .chain([&specialization[..]])
+                    .collect::<Vec<&[u8]>>();
+                Some(cache.key(name, &parts))
+            }
+            None => None,
+        };
+
+        // Use cached binary if there is one
+        let cached_id = match (cache, cache_key) {
+            (Some(cache), Some(key)) => cache.load(gl, key),
+            _ => None,
+        };
+
+        let mut program = match cached_id {
+            Some(id) => Program::from_id(gl, id),
+            None => {
+                // Compile stages, binaries which fail to specialize fall back to the source
+                let shaders = stage_names
+                    .iter()
+                    .map(|resource_name| {
+                        Shader::from_res_specialized(gl, res, resource_name, specialization)
+                            .or_else(|e| match resource_name.strip_suffix(SPIRV_EXT) {
+                                Some(source_name) if res.exists(source_name) => {
+                                    eprintln!("{}, using {} instead", e, source_name);
+                                    Shader::from_res(gl, res, source_name)
+                                }
+                                _ => Err(e),
+                            })
+                    })
+                    .collect::<Result<Vec<Shader>, Error>>()?;
+
+                // Create program from shaders
+                let program = Program::from_shaders(gl, &shaders[..]).map_err(|message| {
+                    Error::LinkError {
+                        name: name.into(),
+                        message,
+                    }
+                })?;
+
+                // Store binary for next run, failing to do so only costs startup time
+                if let (Some(cache), Some(key)) = (cache, cache_key) {
+                    if let Err(e) = cache.store(gl, key, program.id) {
+                        eprintln!("Failed to cache program {}: {}", name, e);
+                    }
+                }
+
+                program
+            }
+        };
+        program.name = Some(name.into());
+        program.source_versions = source_versions;
+        program.specialization = specialization.clone();
+
+        Ok(program)
+    }
+
+    // Function to recompile and relink program if any of its shader resources changed since
+    // it was loaded (requires Resources::watch). On failure the current program is kept
+    // and the compile or link error is returned.
+    pub fn reload_if_changed(&mut self, res: &Resources) -> Result<bool, Error> {
+        let name = match self.name {
+            Some(ref name) => name.clone(),
+            None => return Ok(false),
+        };
+
+        // Check if any source has a newer version
+        let changed = self
+            .source_versions
+            .iter()
+            .any(|(resource_name, version)| res.resource_version(resource_name) != *version);
+        if !changed {
+            return Ok(false);
+        }
+
+        match Program::from_res_specialized(&self.gl, res, &name, &self.specialization) {
+            Ok(program) => {
+                *self = program;
+                Ok(true)
+            }
+            Err(e) => {
+                // Don't retry until sources change again
+                for (resource_name, version) in self.source_versions.iter_mut() {
+                    *version = res.resource_version(resource_name);
+                }
+                Err(e)
+            }
+        }
     }
 
     // Function to create program from shaders
@@ -70,8 +335,13 @@ impl Program {
             }
         }
 
-        // Link program
+        // Link program, keeping its binary retrievable for ProgramCache
         unsafe {
+            gl.ProgramParameteri(
+                program_id,
+                gl::PROGRAM_BINARY_RETRIEVABLE_HINT,
+                gl::TRUE as gl::types::GLint,
+            );
             gl.LinkProgram(program_id);
         }
 
@@ -108,10 +378,20 @@ impl Program {
             }
         }
 
-        Ok(Program {
+        Ok(Program::from_id(gl, program_id))
+    }
+
+    // Helper function to wrap a linked program object, locations of its active uniforms are
+    // cached right away so setting them never queries the driver
+    fn from_id(gl: &gl::Gl, id: gl::types::GLuint) -> Program {
+        Program {
             gl: gl.clone(),
-            id: program_id,
-        })
+            id,
+            uniform_locations: RefCell::new(active_uniform_locations(gl, id)),
+            name: None,
+            source_versions: Vec::new(),
+            specialization: Specialization::new(),
+        }
     }
 
     // Function to get program id
@@ -125,6 +405,110 @@ impl Program {
             self.gl.UseProgram(self.id);
         }
     }
+
+    // Function to check whether the program can execute with the current gl state (sampler
+    // uniforms, bound textures, vertex array), returns the warnings of the driver (empty if
+    // there are none) or why draws would fail, e.g. samplers of different types sharing a unit
+    pub fn validate(&self) -> Result<String, String> {
+        validate_program(&self.gl, self.id)
+    }
+
+    // Function to get uniform location by name, active uniforms are cached at link time and
+    // other names are queried once and cached afterwards (returns -1 for unknown or
+    // optimized out uniforms, which GL silently ignores)
+    pub fn uniform_location(&self, name: &str) -> gl::types::GLint {
+        if let Some(&location) = self.uniform_locations.borrow().get(name) {
+            return location;
+        }
+
+        let location = match CString::new(name) {
+            Ok(c_name) => unsafe { self.gl.GetUniformLocation(self.id, c_name.as_ptr()) },
+            Err(_) => -1,
+        };
+        self.uniform_locations
+            .borrow_mut()
+            .insert(name.to_owned(), location);
+
+        location
+    }
+
+    // Function to connect a uniform block declared in the shader to a binding point, buffers
+    // bound there (e.g. with UniformBuffer::bind_base) feed the block. Returns false if
+    // the program has no such block.
+    pub fn bind_uniform_block(&self, name: &str, binding: u32) -> bool {
+        let index = match CString::new(name) {
+            Ok(c_name) => unsafe { self.gl.GetUniformBlockIndex(self.id, c_name.as_ptr()) },
+            Err(_) => gl::INVALID_INDEX,
+        };
+        if index == gl::INVALID_INDEX {
+            return false;
+        }
+
+        unsafe {
+            self.gl.UniformBlockBinding(self.id, index, binding);
+        }
+        true
+    }
+
+    // Function to connect a shader storage block declared in the shader to a binding point,
+    // buffers bound there (e.g. with ShaderStorageBuffer::bind_base) back the block.
+    // Returns false if the program has no such block.
+    pub fn bind_storage_block(&self, name: &str, binding: u32) -> bool {
+        let index = match CString::new(name) {
+            Ok(c_name) => unsafe {
+                self.gl
+                    .GetProgramResourceIndex(self.id, gl::SHADER_STORAGE_BLOCK, c_name.as_ptr())
+            },
+            Err(_) => gl::INVALID_INDEX,
+        };
+        if index == gl::INVALID_INDEX {
+            return false;
+        }
+
+        unsafe {
+            self.gl.ShaderStorageBlockBinding(self.id, index, binding);
+        }
+        true
+    }
+
+    // Function to set float uniform (program must be in use)
+    pub fn set_uniform_1f(&self, name: &str, value: f32) {
+        unsafe {
+            self.gl.Uniform1f(self.uniform_location(name), value);
+        }
+    }
+
+    // Function to set integer uniform, e.g. sampler texture unit (program must be in use)
+    pub fn set_uniform_1i(&self, name: &str, value: i32) {
+        unsafe {
+            self.gl.Uniform1i(self.uniform_location(name), value);
+        }
+    }
+
+    // Function to set bool uniform (program must be in use)
+    pub fn set_uniform_bool(&self, name: &str, value: bool) {
+        self.set_uniform_1i(name, value as i32);
+    }
+
+    // Function to set vec3 uniform (program must be in use)
+    pub fn set_uniform_vec3(&self, name: &str, value: Vec3) {
+        unsafe {
+            self.gl
+                .Uniform3f(self.uniform_location(name), value.x, value.y, value.z);
+        }
+    }
+
+    // Function to set mat4 uniform (program must be in use)
+    pub fn set_uniform_mat4(&self, name: &str, value: &Mat4) {
+        unsafe {
+            self.gl.UniformMatrix4fv(
+                self.uniform_location(name), // location
+                1,                           // number of matrices
+                gl::FALSE,                   // transpose
+                value.as_ref().as_ptr(),     // pointer to column-major data
+            );
+        }
+    }
 }
 
 // Drop trait implementation for program
@@ -133,9 +517,139 @@ impl Drop for Program {
         unsafe {
             self.gl.DeleteProgram(self.id);
         }
+        // The id may be reused by a new program, which has to be validated again
+        let _ = VALIDATED_PIPELINES.try_with(|validated| {
+            validated
+                .borrow_mut()
+                .retain(|&[program, _, _]| program != self.id)
+        });
     }
 }
 
+// Function to validate the currently used program before its first draw with the bound
+// vertex array and draw framebuffer, printing failures and warnings which would otherwise go
+// unnoticed. Does nothing in release builds or without a used program.
+pub(crate) fn validate_before_draw(gl: &gl::Gl) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let mut program = 0;
+    let mut vertex_array = 0;
+    let mut framebuffer = 0;
+    unsafe {
+        gl.GetIntegerv(gl::CURRENT_PROGRAM, &mut program);
+        gl.GetIntegerv(gl::VERTEX_ARRAY_BINDING, &mut vertex_array);
+        gl.GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut framebuffer);
+    }
+    if program == 0 {
+        return;
+    }
+
+    let pipeline = [program as u32, vertex_array as u32, framebuffer as u32];
+    let first_draw = VALIDATED_PIPELINES.with(|validated| validated.borrow_mut().insert(pipeline));
+    if !first_draw {
+        return;
+    }
+
+    match validate_program(gl, pipeline[0]) {
+        Ok(ref warnings) if warnings.trim().is_empty() => {}
+        Ok(warnings) => eprintln!("Program {} validation warnings: {}", program, warnings),
+        Err(message) => eprintln!("Program {} failed validation: {}", program, message),
+    }
+}
+
+// Helper function to run glValidateProgram and collect its info log
+fn validate_program(gl: &gl::Gl, program: gl::types::GLuint) -> Result<String, String> {
+    let mut success: gl::types::GLint = 0;
+    let mut len: gl::types::GLint = 0;
+    unsafe {
+        gl.ValidateProgram(program);
+        gl.GetProgramiv(program, gl::VALIDATE_STATUS, &mut success);
+        gl.GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
+    }
+
+    let mut log = String::new();
+    if len > 0 {
+        let buffer = create_whitespace_cstring_with_len(len as usize);
+        unsafe {
+            gl.GetProgramInfoLog(
+                program,
+                len,
+                std::ptr::null_mut(),
+                buffer.as_ptr() as *mut gl::types::GLchar,
+            );
+        }
+        log = buffer.to_string_lossy().into_owned();
+    }
+
+    if success == 0 {
+        Err(log)
+    } else {
+        Ok(log)
+    }
+}
+
+// Helper function to pick the resources to compile among the possible stage names. Binaries
+// are used when SPIR-V is supported, otherwise the GLSL source of the stage. A stage which has
+// only one of them keeps it, so a missing source is reported instead of a missing stage.
+fn select_stages<F>(resource_names: &[String], exists: F, spirv_supported: bool) -> Vec<&String>
+where
+    F: Fn(&str) -> bool,
+{
+    resource_names
+        .iter()
+        .filter(|resource_name| exists(resource_name))
+        .filter(|resource_name| match resource_name.strip_suffix(SPIRV_EXT) {
+            Some(source_name) => spirv_supported || !exists(source_name),
+            None => !spirv_supported || !exists(&format!("{}{}", resource_name, SPIRV_EXT)),
+        })
+        .collect()
+}
+
+// Helper function to get locations of the active uniforms of a linked program, arrays are
+// also found by their name without [0]. Uniforms in blocks have no location and are left out.
+fn active_uniform_locations(
+    gl: &gl::Gl,
+    program: gl::types::GLuint,
+) -> HashMap<String, gl::types::GLint> {
+    let mut count: gl::types::GLint = 0;
+    let mut max_length: gl::types::GLint = 0;
+    unsafe {
+        gl.GetProgramInterfaceiv(program, gl::UNIFORM, gl::ACTIVE_RESOURCES, &mut count);
+        gl.GetProgramInterfaceiv(program, gl::UNIFORM, gl::MAX_NAME_LENGTH, &mut max_length);
+    }
+
+    let mut locations = HashMap::new();
+    let mut name = vec![0u8; max_length.max(1) as usize];
+    for index in 0..count.max(0) as gl::types::GLuint {
+        let mut length: gl::types::GLsizei = 0;
+        let location = unsafe {
+            gl.GetProgramResourceName(
+                program,
+                gl::UNIFORM,
+                index,
+                name.len() as gl::types::GLsizei,
+                &mut length,
+                name.as_mut_ptr() as *mut gl::types::GLchar,
+            );
+            let c_name = name.as_ptr() as *const gl::types::GLchar;
+            gl.GetProgramResourceLocation(program, gl::UNIFORM, c_name)
+        };
+        if location < 0 {
+            continue;
+        }
+
+        let uniform_name = String::from_utf8_lossy(&name[..length as usize]).into_owned();
+        if let Some(array_name) = uniform_name.strip_suffix("[0]") {
+            locations.insert(array_name.to_owned(), location);
+        }
+        locations.insert(uniform_name, location);
+    }
+
+    locations
+}
+
 // Newtype wrapper for shader
 pub struct Shader {
     gl: gl::Gl,
@@ -144,19 +658,43 @@ pub struct Shader {
 
 // Implementation of shader
 impl Shader {
-    // Function to create shader from resource
+    // Function to create shader from resource, GLSL source or SPIR-V binary (.spv)
     pub fn from_res(gl: &gl::Gl, res: &Resources, name: &str) -> Result<Shader, Error> {
-        // Array of possible extensions
-        const POSSIBLE_EXT: [(&str, gl::types::GLenum); 2] =
-            [(".vert", gl::VERTEX_SHADER), (".frag", gl::FRAGMENT_SHADER)];
+        Shader::from_res_specialized(gl, res, name, &Specialization::new())
+    }
 
+    // Function to create shader from resource, SPIR-V binaries are specialized with the
+    // given entry point and constants
+    pub fn from_res_specialized(
+        gl: &gl::Gl,
+        res: &Resources,
+        name: &str,
+        specialization: &Specialization,
+    ) -> Result<Shader, Error> {
         // Get shader kind
-        let shader_kind = POSSIBLE_EXT
+        let stage_name = name.strip_suffix(SPIRV_EXT).unwrap_or(name);
+        let shader_kind = SHADER_EXT
             .iter()
-            .find(|&&(file_extension, _)| name.ends_with(file_extension))
+            .chain(std::iter::once(&COMPUTE_EXT))
+            .find(|&&(file_extension, _)| stage_name.ends_with(file_extension))
             .map(|&(_, kind)| kind)
             .ok_or_else(|| Error::CanNotDetermineShaderTypeForResource { name: name.into() })?;
 
+        // Load and specialize SPIR-V binary
+        if stage_name.len() != name.len() {
+            let binary = res.load_bytes(name).map_err(|e| Error::ResourceLoad {
+                name: name.into(),
+                inner: e,
+            })?;
+
+            return Shader::from_spirv(gl, &binary, shader_kind, specialization).map_err(
+                |message| Error::CompileError {
+                    name: name.into(),
+                    message,
+                },
+            );
+        }
+
         // Load shader source
         let source = res.load_cstring(name).map_err(|e| Error::ResourceLoad {
             name: name.into(),
@@ -180,6 +718,48 @@ impl Shader {
         Ok(Shader { gl: gl.clone(), id })
     }
 
+    // Function to create shader from SPIR-V binary, fails without Extensions::has_spirv
+    pub fn from_spirv(
+        gl: &gl::Gl,
+        binary: &[u8],
+        kind: gl::types::GLenum,
+        specialization: &Specialization,
+    ) -> Result<Shader, String> {
+        if !gl.SpecializeShaderARB.is_loaded() {
+            return Err(String::from("SPIR-V shaders are not supported by the driver"));
+        }
+        let entry_point = CString::new(specialization.entry_point.as_str())
+            .map_err(|_| String::from("Entry point name contains 0"))?;
+        let (indices, values): (Vec<u32>, Vec<u32>) =
+            specialization.constants.iter().copied().unzip();
+
+        let id = unsafe { gl.CreateShader(kind) };
+        // Shader is deleted on drop, also when specialization fails
+        let shader = Shader { gl: gl.clone(), id };
+
+        unsafe {
+            gl.ShaderBinary(
+                1,                                           // number of shaders
+                &id,                                         // shaders
+                gl::SHADER_BINARY_FORMAT_SPIR_V_ARB,         // binary format
+                binary.as_ptr() as *const gl::types::GLvoid, // binary
+                binary.len() as gl::types::GLsizei,          // binary length
+            );
+            gl.SpecializeShaderARB(
+                id,
+                entry_point.as_ptr(),
+                indices.len() as gl::types::GLuint,
+                indices.as_ptr(),
+                values.as_ptr(),
+            );
+        }
+
+        // Specialization reports errors the same way as compilation
+        check_compile_status(gl, id)?;
+
+        Ok(shader)
+    }
+
     // Function to create vertex shader from file
     pub fn from_vert_source(gl: &gl::Gl, source: &CStr) -> Result<Shader, String> {
         Shader::from_source(gl, source, gl::VERTEX_SHADER)
@@ -190,6 +770,26 @@ impl Shader {
         Shader::from_source(gl, source, gl::FRAGMENT_SHADER)
     }
 
+    // Function to create tessellation control shader from file
+    pub fn from_tesc_source(gl: &gl::Gl, source: &CStr) -> Result<Shader, String> {
+        Shader::from_source(gl, source, gl::TESS_CONTROL_SHADER)
+    }
+
+    // Function to create tessellation evaluation shader from file
+    pub fn from_tese_source(gl: &gl::Gl, source: &CStr) -> Result<Shader, String> {
+        Shader::from_source(gl, source, gl::TESS_EVALUATION_SHADER)
+    }
+
+    // Function to create compute shader from file
+    pub fn from_comp_source(gl: &gl::Gl, source: &CStr) -> Result<Shader, String> {
+        Shader::from_source(gl, source, gl::COMPUTE_SHADER)
+    }
+
+    // Function to create geometry shader from file
+    pub fn from_geom_source(gl: &gl::Gl, source: &CStr) -> Result<Shader, String> {
+        Shader::from_source(gl, source, gl::GEOMETRY_SHADER)
+    }
+
     // Function to get shader id
     pub fn id(&self) -> gl::types::GLuint {
         self.id
@@ -221,6 +821,18 @@ fn shader_from_source(
     }
 
     // Check if compilation was successful
+    if let Err(error) = check_compile_status(gl, id) {
+        unsafe {
+            gl.DeleteShader(id);
+        }
+        return Err(error);
+    }
+
+    Ok(id)
+}
+
+// Helper function to get the info log of a shader if its compilation failed
+fn check_compile_status(gl: &gl::Gl, id: gl::types::GLuint) -> Result<(), String> {
     let mut success: gl::types::GLint = 1;
     unsafe {
         gl.GetShaderiv(id, gl::COMPILE_STATUS, &mut success);
@@ -248,7 +860,7 @@ fn shader_from_source(
         }
     }
 
-    Ok(id)
+    Ok(())
 }
 
 // Function to create whitespace cstring with length
@@ -260,3 +872,55 @@ fn create_whitespace_cstring_with_len(len: usize) -> CString {
     // convert buffer to CString
     unsafe { CString::from_vec_unchecked(buffer) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stages(existing: &[&str], spirv_supported: bool) -> Vec<String> {
+        let resource_names = [
+            "triangle.vert.spv",
+            "triangle.vert",
+            "triangle.frag.spv",
+            "triangle.frag",
+        ]
+        .map(String::from);
+        select_stages(
+            &resource_names,
+            |name| existing.contains(&name),
+            spirv_supported,
+        )
+        .into_iter()
+        .cloned()
+        .collect()
+    }
+
+    #[test]
+    fn sources_are_used_without_spirv_support() {
+        let existing = [
+            "triangle.vert.spv",
+            "triangle.vert",
+            "triangle.frag.spv",
+            "triangle.frag",
+        ];
+        assert_eq!(stages(&existing, false), ["triangle.vert", "triangle.frag"]);
+    }
+
+    #[test]
+    fn binaries_are_preferred_with_spirv_support() {
+        let existing = ["triangle.vert.spv", "triangle.vert", "triangle.frag"];
+        assert_eq!(
+            stages(&existing, true),
+            ["triangle.vert.spv", "triangle.frag"]
+        );
+    }
+
+    #[test]
+    fn stages_with_only_a_binary_keep_it() {
+        let existing = ["triangle.vert.spv", "triangle.frag"];
+        assert_eq!(
+            stages(&existing, false),
+            ["triangle.vert.spv", "triangle.frag"]
+        );
+    }
+}
@@ -0,0 +1,96 @@
+// Import dependencies
+use gl;
+
+use crate::math::Vec3;
+use crate::render::buffer::VertexArray;
+use crate::render::camera::Camera;
+use crate::render::shader::validate_before_draw;
+use crate::render::state::StateScope;
+use crate::render::{Error, Program};
+use crate::resources::Resources;
+
+// Struct that represents a procedural daylight sky (Preetham model) drawn as a fullscreen
+// background pass, the sun direction is meant to be shared with the directional light so
+// sky and lighting agree at any time of day
+pub struct Sky {
+    // The gl context
+    gl: gl::Gl,
+    // Program linked from shaders/sky.vert and shaders/sky.frag
+    program: Program,
+    // Empty vertex array, the fullscreen triangle is generated from gl_VertexID
+    vao: VertexArray,
+    // Direction towards the sun, normalized
+    sun_direction: Vec3,
+    // Haziness of the atmosphere, 2 is a clear sky and 10 a hazy one
+    turbidity: f32,
+    // Scale applied to sky luminance before tone mapping
+    exposure: f32,
+}
+
+// Implementation of sky
+impl Sky {
+    // Function to create sky from the shaders/sky program with the sun high in the south
+    pub fn from_res(gl: &gl::Gl, res: &Resources) -> Result<Sky, Error> {
+        Ok(Sky {
+            gl: gl.clone(),
+            program: Program::from_res(gl, res, "shaders/sky")?,
+            vao: VertexArray::new(gl),
+            sun_direction: Vec3::new(0.0, 0.8, -0.6).normalize(),
+            turbidity: 2.5,
+            exposure: 0.1,
+        })
+    }
+
+    // Function to get direction towards the sun, e.g. to light the scene with
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    // Function to set direction towards the sun, y is up
+    pub fn set_sun_direction(&mut self, direction: Vec3) {
+        self.sun_direction = direction.normalize_or_zero();
+    }
+
+    // Function to get atmosphere turbidity
+    pub fn turbidity(&self) -> f32 {
+        self.turbidity
+    }
+
+    // Function to set atmosphere turbidity, the model is valid from 2 to 10
+    pub fn set_turbidity(&mut self, turbidity: f32) {
+        self.turbidity = turbidity.clamp(2.0, 10.0);
+    }
+
+    // Function to set scale applied to sky luminance before tone mapping
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.exposure = exposure;
+    }
+
+    // Function to rebuild the sky program if its sources changed
+    pub fn reload_if_changed(&mut self, res: &Resources) -> Result<bool, Error> {
+        self.program.reload_if_changed(res)
+    }
+
+    // Function to draw the sky behind everything, either before the scene or after opaque
+    // geometry with depth test on (the sky is drawn at the far plane without writing depth)
+    pub fn draw(&self, camera: &Camera) {
+        let mut state = StateScope::new(&self.gl);
+        state.set_depth(gl::LEQUAL, false);
+
+        let inverse_view_projection = (camera.projection_matrix() * camera.view_matrix()).inverse();
+        let program = &self.program;
+        program.set_used();
+        program.set_uniform_mat4("InverseViewProjection", &inverse_view_projection);
+        program.set_uniform_vec3("CameraPosition", camera.position());
+        program.set_uniform_vec3("SunDirection", self.sun_direction);
+        program.set_uniform_1f("Turbidity", self.turbidity);
+        program.set_uniform_1f("Exposure", self.exposure);
+
+        self.vao.bind();
+        validate_before_draw(&self.gl);
+        unsafe {
+            self.gl.DrawArrays(gl::TRIANGLES, 0, 3);
+        }
+        self.vao.unbind();
+    }
+}
@@ -0,0 +1,471 @@
+// Import dependencies
+use gl;
+use gl::types::{GLboolean, GLenum, GLint, GLuint};
+
+// Enum that represents a piece of gl state saved by a scope
+enum Saved {
+    // Enabled state of a capability (e.g. gl::BLEND)
+    Capability(GLenum, bool),
+    Viewport([GLint; 4]),
+    ScissorBox([GLint; 4]),
+    // Source rgb, destination rgb, source alpha, destination alpha
+    BlendFunc([GLenum; 4]),
+    // Rgb, alpha
+    BlendEquation([GLenum; 2]),
+    DepthFunc(GLenum),
+    DepthMask(bool),
+    // Function, reference value, read mask
+    StencilFunc(GLenum, GLint, GLuint),
+    // Stencil fail, depth fail, depth pass
+    StencilOp([GLenum; 3]),
+    StencilMask(GLuint),
+    ColorMask([bool; 4]),
+    CullFace(GLenum),
+    PolygonMode(GLenum),
+    Program(GLuint),
+    VertexArray(GLuint),
+    ArrayBuffer(GLuint),
+    // Draw and read framebuffers
+    Framebuffer(GLuint, GLuint),
+    ActiveTexture(GLenum),
+    // Texture unit, texture bound to gl::TEXTURE_2D on it
+    Texture2D(GLenum, GLuint),
+}
+
+// Struct that represents stencil test settings, applied to front and back faces alike
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StencilState {
+    // Comparison of reference against the stored value, e.g. gl::EQUAL
+    pub func: GLenum,
+    pub reference: GLint,
+    // Bits compared by the test
+    pub read_mask: GLuint,
+    // Bits written by the operations
+    pub write_mask: GLuint,
+    // Operations when the stencil test fails, the depth test fails and both pass,
+    // e.g. gl::KEEP or gl::REPLACE
+    pub stencil_fail: GLenum,
+    pub depth_fail: GLenum,
+    pub pass: GLenum,
+}
+
+// Implementation of stencil state
+impl StencilState {
+    // Constructor for a state writing reference wherever something is drawn, e.g. to mark
+    // the pixels of an object for an outline pass
+    pub fn write(reference: GLint) -> StencilState {
+        StencilState {
+            func: gl::ALWAYS,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0xFF,
+            stencil_fail: gl::KEEP,
+            depth_fail: gl::KEEP,
+            pass: gl::REPLACE,
+        }
+    }
+
+    // Constructor for a state passing only where the stored value compares to reference
+    // with func, without changing stored values
+    pub fn test(func: GLenum, reference: GLint) -> StencilState {
+        StencilState {
+            func,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0x00,
+            stencil_fail: gl::KEEP,
+            depth_fail: gl::KEEP,
+            pass: gl::KEEP,
+        }
+    }
+}
+
+// Struct that represents depth and stencil test settings, applied at once either directly
+// or through a StateScope which restores the previous settings
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DepthStencilState {
+    pub depth_test: bool,
+    // Comparison of incoming against stored depth, e.g. gl::LESS
+    pub depth_func: GLenum,
+    pub depth_write: bool,
+    // Stencil test settings, None disables the stencil test
+    pub stencil: Option<StencilState>,
+}
+
+// Implementation of depth stencil state
+impl DepthStencilState {
+    // State with depth and stencil tests disabled, e.g. for fullscreen passes
+    pub const DISABLED: DepthStencilState = DepthStencilState {
+        depth_test: false,
+        depth_func: gl::LESS,
+        depth_write: false,
+        stencil: None,
+    };
+
+    // Constructor for the usual state of opaque geometry, nearer fragments win and write
+    // their depth
+    pub fn new() -> DepthStencilState {
+        DepthStencilState {
+            depth_test: true,
+            depth_func: gl::LESS,
+            depth_write: true,
+            stencil: None,
+        }
+    }
+
+    // Function to set the state without saving the previous one
+    pub fn apply(&self, gl: &gl::Gl) {
+        unsafe {
+            if self.depth_test {
+                gl.Enable(gl::DEPTH_TEST);
+            } else {
+                gl.Disable(gl::DEPTH_TEST);
+            }
+            gl.DepthFunc(self.depth_func);
+            gl.DepthMask(self.depth_write as GLboolean);
+
+            match self.stencil {
+                Some(stencil) => {
+                    gl.Enable(gl::STENCIL_TEST);
+                    gl.StencilFunc(stencil.func, stencil.reference, stencil.read_mask);
+                    gl.StencilOp(stencil.stencil_fail, stencil.depth_fail, stencil.pass);
+                    gl.StencilMask(stencil.write_mask);
+                }
+                None => gl.Disable(gl::STENCIL_TEST),
+            }
+        }
+    }
+}
+
+// Implement default trait for depth stencil state
+impl Default for DepthStencilState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Struct that represents a scope which records the gl state it changes and restores it
+// on drop, in reverse order of changes
+pub struct StateScope {
+    // The gl context
+    gl: gl::Gl,
+    // State to restore, in the order it was saved
+    saved: Vec<Saved>,
+}
+
+// Implementation of state scope
+impl StateScope {
+    // Constructor for an empty scope, state is saved by the setters before changing it
+    pub fn new(gl: &gl::Gl) -> StateScope {
+        StateScope {
+            gl: gl.clone(),
+            saved: Vec::new(),
+        }
+    }
+
+    // Constructor for a scope which saves all state commonly changed by ui renderers
+    // (egui, imgui, ...), so it can be restored after calling into them
+    pub fn capture(gl: &gl::Gl) -> StateScope {
+        let mut scope = StateScope::new(gl);
+        for &capability in &[
+            gl::BLEND,
+            gl::CULL_FACE,
+            gl::DEPTH_TEST,
+            gl::STENCIL_TEST,
+            gl::SCISSOR_TEST,
+            gl::FRAMEBUFFER_SRGB,
+        ] {
+            scope.save_capability(capability);
+        }
+        scope.save_viewport();
+        scope.save_scissor_box();
+        scope.save_blend();
+        scope.save_depth();
+        scope.save_color_mask();
+        scope.save_cull_face();
+        scope.save_polygon_mode();
+        scope.save_bindings();
+        scope
+    }
+
+    // Function to enable or disable a capability
+    pub fn set_capability(&mut self, capability: GLenum, enabled: bool) {
+        self.save_capability(capability);
+        unsafe {
+            if enabled {
+                self.gl.Enable(capability);
+            } else {
+                self.gl.Disable(capability);
+            }
+        }
+    }
+
+    // Function to set the viewport
+    pub fn set_viewport(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.save_viewport();
+        unsafe {
+            self.gl.Viewport(x, y, width, height);
+        }
+    }
+
+    // Function to bind a framebuffer for drawing and reading, 0 is the window
+    pub fn set_framebuffer(&mut self, framebuffer: GLuint) {
+        self.save_framebuffer();
+        unsafe {
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+        }
+    }
+
+    // Function to set the scissor box, scissor test has to be enabled separately
+    pub fn set_scissor(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.save_scissor_box();
+        unsafe {
+            self.gl.Scissor(x, y, width, height);
+        }
+    }
+
+    // Function to set blend function and equation for color and alpha
+    pub fn set_blend(&mut self, src: GLenum, dst: GLenum, equation: GLenum) {
+        self.save_blend();
+        unsafe {
+            self.gl.BlendFunc(src, dst);
+            self.gl.BlendEquation(equation);
+        }
+    }
+
+    // Function to set depth comparison function and depth writes
+    pub fn set_depth(&mut self, func: GLenum, write: bool) {
+        self.save_depth();
+        unsafe {
+            self.gl.DepthFunc(func);
+            self.gl.DepthMask(write as GLboolean);
+        }
+    }
+
+    // Function to set depth and stencil tests
+    pub fn set_depth_stencil(&mut self, state: &DepthStencilState) {
+        self.save_capability(gl::DEPTH_TEST);
+        self.save_capability(gl::STENCIL_TEST);
+        self.save_depth();
+        self.save_stencil();
+        state.apply(&self.gl);
+    }
+
+    // Function to set which color channels are written
+    pub fn set_color_mask(&mut self, r: bool, g: bool, b: bool, a: bool) {
+        self.save_color_mask();
+        unsafe {
+            self.gl.ColorMask(
+                r as GLboolean,
+                g as GLboolean,
+                b as GLboolean,
+                a as GLboolean,
+            );
+        }
+    }
+
+    // Function to set which faces are culled
+    pub fn set_cull_face(&mut self, face: GLenum) {
+        self.save_cull_face();
+        unsafe {
+            self.gl.CullFace(face);
+        }
+    }
+
+    // Function to set polygon rasterization mode (gl::FILL, gl::LINE or gl::POINT)
+    pub fn set_polygon_mode(&mut self, mode: GLenum) {
+        self.save_polygon_mode();
+        unsafe {
+            self.gl.PolygonMode(gl::FRONT_AND_BACK, mode);
+        }
+    }
+
+    // Function to save enabled state of a capability
+    pub fn save_capability(&mut self, capability: GLenum) {
+        let enabled = unsafe { self.gl.IsEnabled(capability) } == gl::TRUE;
+        self.saved.push(Saved::Capability(capability, enabled));
+    }
+
+    // Function to save the viewport
+    pub fn save_viewport(&mut self) {
+        let viewport = self.get_integers::<4>(gl::VIEWPORT);
+        self.saved.push(Saved::Viewport(viewport));
+    }
+
+    // Function to save the scissor box
+    pub fn save_scissor_box(&mut self) {
+        let scissor_box = self.get_integers::<4>(gl::SCISSOR_BOX);
+        self.saved.push(Saved::ScissorBox(scissor_box));
+    }
+
+    // Function to save blend function and equation
+    pub fn save_blend(&mut self) {
+        let func = [
+            self.get_integer(gl::BLEND_SRC_RGB) as GLenum,
+            self.get_integer(gl::BLEND_DST_RGB) as GLenum,
+            self.get_integer(gl::BLEND_SRC_ALPHA) as GLenum,
+            self.get_integer(gl::BLEND_DST_ALPHA) as GLenum,
+        ];
+        let equation = [
+            self.get_integer(gl::BLEND_EQUATION_RGB) as GLenum,
+            self.get_integer(gl::BLEND_EQUATION_ALPHA) as GLenum,
+        ];
+        self.saved.push(Saved::BlendFunc(func));
+        self.saved.push(Saved::BlendEquation(equation));
+    }
+
+    // Function to save depth comparison function and depth writes
+    pub fn save_depth(&mut self) {
+        let func = self.get_integer(gl::DEPTH_FUNC) as GLenum;
+        let write = self.get_boolean(gl::DEPTH_WRITEMASK);
+        self.saved.push(Saved::DepthFunc(func));
+        self.saved.push(Saved::DepthMask(write));
+    }
+
+    // Function to save stencil function, operations and write mask (of front faces, they
+    // are restored for both faces)
+    pub fn save_stencil(&mut self) {
+        let func = self.get_integer(gl::STENCIL_FUNC) as GLenum;
+        let reference = self.get_integer(gl::STENCIL_REF);
+        let read_mask = self.get_integer(gl::STENCIL_VALUE_MASK) as GLuint;
+        let ops = [
+            self.get_integer(gl::STENCIL_FAIL) as GLenum,
+            self.get_integer(gl::STENCIL_PASS_DEPTH_FAIL) as GLenum,
+            self.get_integer(gl::STENCIL_PASS_DEPTH_PASS) as GLenum,
+        ];
+        let write_mask = self.get_integer(gl::STENCIL_WRITEMASK) as GLuint;
+        self.saved
+            .push(Saved::StencilFunc(func, reference, read_mask));
+        self.saved.push(Saved::StencilOp(ops));
+        self.saved.push(Saved::StencilMask(write_mask));
+    }
+
+    // Function to save color write mask
+    pub fn save_color_mask(&mut self) {
+        let mut mask: [GLboolean; 4] = [gl::TRUE; 4];
+        unsafe {
+            self.gl.GetBooleanv(gl::COLOR_WRITEMASK, mask.as_mut_ptr());
+        }
+        self.saved
+            .push(Saved::ColorMask(mask.map(|channel| channel == gl::TRUE)));
+    }
+
+    // Function to save culled faces
+    pub fn save_cull_face(&mut self) {
+        let face = self.get_integer(gl::CULL_FACE_MODE) as GLenum;
+        self.saved.push(Saved::CullFace(face));
+    }
+
+    // Function to save polygon rasterization mode (core profile has a single mode for
+    // front and back faces)
+    pub fn save_polygon_mode(&mut self) {
+        let mode = self.get_integers::<2>(gl::POLYGON_MODE)[0] as GLenum;
+        self.saved.push(Saved::PolygonMode(mode));
+    }
+
+    // Function to save bound draw and read framebuffers
+    pub fn save_framebuffer(&mut self) {
+        let draw_framebuffer = self.get_integer(gl::DRAW_FRAMEBUFFER_BINDING) as GLuint;
+        let read_framebuffer = self.get_integer(gl::READ_FRAMEBUFFER_BINDING) as GLuint;
+        self.saved
+            .push(Saved::Framebuffer(draw_framebuffer, read_framebuffer));
+    }
+
+    // Function to save bound program, vertex array, array buffer, framebuffers and the
+    // 2D texture of the active texture unit
+    pub fn save_bindings(&mut self) {
+        let program = self.get_integer(gl::CURRENT_PROGRAM) as GLuint;
+        let vertex_array = self.get_integer(gl::VERTEX_ARRAY_BINDING) as GLuint;
+        let array_buffer = self.get_integer(gl::ARRAY_BUFFER_BINDING) as GLuint;
+        let active_texture = self.get_integer(gl::ACTIVE_TEXTURE) as GLenum;
+        let texture = self.get_integer(gl::TEXTURE_BINDING_2D) as GLuint;
+
+        self.saved.push(Saved::Program(program));
+        self.saved.push(Saved::VertexArray(vertex_array));
+        self.saved.push(Saved::ArrayBuffer(array_buffer));
+        self.save_framebuffer();
+        // Texture has to be restored before the active unit is switched back
+        self.saved.push(Saved::ActiveTexture(active_texture));
+        self.saved.push(Saved::Texture2D(active_texture, texture));
+    }
+
+    // Helper function to query a single integer
+    fn get_integer(&self, name: GLenum) -> GLint {
+        self.get_integers::<1>(name)[0]
+    }
+
+    // Helper function to query an integer array
+    fn get_integers<const N: usize>(&self, name: GLenum) -> [GLint; N] {
+        let mut values = [0; N];
+        unsafe {
+            self.gl.GetIntegerv(name, values.as_mut_ptr());
+        }
+        values
+    }
+
+    // Helper function to query a single boolean
+    fn get_boolean(&self, name: GLenum) -> bool {
+        let mut value: GLboolean = gl::FALSE;
+        unsafe {
+            self.gl.GetBooleanv(name, &mut value);
+        }
+        value == gl::TRUE
+    }
+
+    // Helper function to restore one piece of saved state
+    fn restore(&self, saved: &Saved) {
+        let gl = &self.gl;
+        unsafe {
+            match *saved {
+                Saved::Capability(capability, true) => gl.Enable(capability),
+                Saved::Capability(capability, false) => gl.Disable(capability),
+                Saved::Viewport([x, y, width, height]) => gl.Viewport(x, y, width, height),
+                Saved::ScissorBox([x, y, width, height]) => gl.Scissor(x, y, width, height),
+                Saved::BlendFunc([src_rgb, dst_rgb, src_alpha, dst_alpha]) => {
+                    gl.BlendFuncSeparate(src_rgb, dst_rgb, src_alpha, dst_alpha)
+                }
+                Saved::BlendEquation([rgb, alpha]) => gl.BlendEquationSeparate(rgb, alpha),
+                Saved::DepthFunc(func) => gl.DepthFunc(func),
+                Saved::DepthMask(write) => gl.DepthMask(write as GLboolean),
+                Saved::StencilFunc(func, reference, read_mask) => {
+                    gl.StencilFunc(func, reference, read_mask)
+                }
+                Saved::StencilOp([stencil_fail, depth_fail, pass]) => {
+                    gl.StencilOp(stencil_fail, depth_fail, pass)
+                }
+                Saved::StencilMask(write_mask) => gl.StencilMask(write_mask),
+                Saved::ColorMask([r, g, b, a]) => gl.ColorMask(
+                    r as GLboolean,
+                    g as GLboolean,
+                    b as GLboolean,
+                    a as GLboolean,
+                ),
+                Saved::CullFace(face) => gl.CullFace(face),
+                Saved::PolygonMode(mode) => gl.PolygonMode(gl::FRONT_AND_BACK, mode),
+                Saved::Program(program) => gl.UseProgram(program),
+                Saved::VertexArray(vertex_array) => gl.BindVertexArray(vertex_array),
+                Saved::ArrayBuffer(buffer) => gl.BindBuffer(gl::ARRAY_BUFFER, buffer),
+                Saved::Framebuffer(draw, read) => {
+                    gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, draw);
+                    gl.BindFramebuffer(gl::READ_FRAMEBUFFER, read);
+                }
+                Saved::ActiveTexture(unit) => gl.ActiveTexture(unit),
+                Saved::Texture2D(unit, texture) => {
+                    gl.ActiveTexture(unit);
+                    gl.BindTexture(gl::TEXTURE_2D, texture);
+                }
+            }
+        }
+    }
+}
+
+// Implement Drop trait for state scope
+impl Drop for StateScope {
+    // Restore saved state, latest saves first so the oldest value of repeatedly saved
+    // state wins
+    fn drop(&mut self) {
+        for saved in self.saved.iter().rev() {
+            self.restore(saved);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// Import dependencies
+use gl;
+
+use crate::render::buffer::{Buffer, BufferData, BufferType, MapAccess};
+
+// Number of regions, the cpu writes one while the gpu may still read the other two
+const REGION_COUNT: usize = 3;
+
+// Struct that represents a persistently mapped ring buffer for data that changes every
+// frame (e.g. particles, UI or debug lines). The buffer is split into one region per frame
+// in flight, each frame writes into its own region and a fence keeps the cpu from
+// overwriting a region before the gpu has finished drawing from it.
+pub struct StreamingBuffer<B>
+where
+    B: BufferType,
+{
+    // The gl context
+    gl: gl::Gl,
+    // Buffer with immutable storage holding all regions
+    buffer: Buffer<B>,
+    // Start of the mapping of the whole buffer
+    pointer: *mut u8,
+    // Size of one region in bytes
+    region_size: usize,
+    // Region written in the current frame
+    region: usize,
+    // Byte offset of the next write within the current region
+    cursor: usize,
+    // Fences signaled once the gpu has finished the frame that wrote each region
+    fences: [gl::types::GLsync; REGION_COUNT],
+}
+
+// Implementation of streaming buffer
+impl<B> StreamingBuffer<B>
+where
+    B: BufferType,
+{
+    // Constructor for a streaming buffer holding region_size bytes per frame
+    pub fn new(gl: &gl::Gl, region_size: usize) -> Self {
+        let flags = MapAccess::WRITE | MapAccess::PERSISTENT | MapAccess::COHERENT;
+        let size = region_size * REGION_COUNT;
+
+        let buffer = Buffer::new(gl);
+        buffer.bind();
+        buffer.allocate_storage(size, flags);
+        let pointer = unsafe {
+            gl.MapBufferRange(
+                B::BUFFER_TYPE,                // target
+                0,                             // offset in bytes
+                size as gl::types::GLsizeiptr, // size of data in bytes
+                flags.to_gl(),                 // access flags
+            )
+        };
+        buffer.unbind();
+
+        Self {
+            gl: gl.clone(),
+            buffer,
+            pointer: pointer as *mut u8,
+            region_size,
+            region: 0,
+            cursor: 0,
+            fences: [::std::ptr::null(); REGION_COUNT],
+        }
+    }
+
+    // Function to get the underlying buffer, e.g. to bind it as vertex source
+    pub fn buffer(&self) -> &Buffer<B> {
+        &self.buffer
+    }
+
+    // Function to get the size of one region in bytes, the most a frame can push
+    pub fn region_size(&self) -> usize {
+        self.region_size
+    }
+
+    // Function to copy data into the current region, returns its byte offset in the buffer
+    // and its length in bytes, or None if the region has no room left for this frame
+    pub fn push<T: BufferData<B> + Copy>(&mut self, data: &[T]) -> Option<(usize, usize)> {
+        self.push_aligned(data, ::std::mem::align_of::<T>())
+    }
+
+    // Function to copy data into the current region starting at a multiple of alignment,
+    // e.g. GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT for ranges bound with bind_range
+    pub fn push_aligned<T: BufferData<B> + Copy>(
+        &mut self,
+        data: &[T],
+        alignment: usize,
+    ) -> Option<(usize, usize)> {
+        if self.pointer.is_null() {
+            return None;
+        }
+
+        // The offset in the whole buffer is aligned, the region size need not be a multiple
+        // of the alignment
+        let region_start = self.region * self.region_size;
+        let offset = (region_start + self.cursor).next_multiple_of(alignment.max(1));
+        let start = offset - region_start;
+        let len = ::std::mem::size_of_val(data);
+        if start + len > self.region_size {
+            return None;
+        }
+
+        unsafe {
+            ::std::ptr::copy_nonoverlapping(
+                data.as_ptr() as *const u8,
+                self.pointer.add(offset),
+                len,
+            );
+        }
+        self.cursor = start + len;
+
+        Some((offset, len))
+    }
+
+    // Function to end the frame after its draw calls are issued, fences the current region
+    // and moves on to the next one, waiting if the gpu is still reading it
+    pub fn finish_frame(&mut self) {
+        unsafe {
+            self.fences[self.region] = self.gl.FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0);
+        }
+
+        self.region = (self.region + 1) % REGION_COUNT;
+        self.cursor = 0;
+        self.wait_for_region(self.region);
+    }
+
+    // Function to block until the gpu has finished the frame that last wrote a region
+    fn wait_for_region(&mut self, region: usize) {
+        let fence = self.fences[region];
+        if fence.is_null() {
+            return;
+        }
+
+        loop {
+            let result = unsafe {
+                self.gl.ClientWaitSync(
+                    fence,
+                    gl::SYNC_FLUSH_COMMANDS_BIT,
+                    1_000_000, // timeout in nanoseconds
+                )
+            };
+            if result != gl::TIMEOUT_EXPIRED {
+                break;
+            }
+        }
+
+        unsafe {
+            self.gl.DeleteSync(fence);
+        }
+        self.fences[region] = ::std::ptr::null();
+    }
+}
+
+// Implement drop trait for the streaming buffer struct
+impl<B> Drop for StreamingBuffer<B>
+where
+    B: BufferType,
+{
+    fn drop(&mut self) {
+        unsafe {
+            for fence in self.fences.iter().filter(|fence| !fence.is_null()) {
+                self.gl.DeleteSync(*fence);
+            }
+        }
+        if !self.pointer.is_null() {
+            self.buffer.bind();
+            unsafe {
+                self.gl.UnmapBuffer(B::BUFFER_TYPE);
+            }
+            self.buffer.unbind();
+        }
+    }
+}
@@ -0,0 +1,682 @@
+// Import dependencies
+use gl;
+
+use crate::image_util::{self, NormalMapConvention};
+use crate::render::noise::{self, NoiseParams};
+use crate::resources::Resources;
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(display = "Failed to load texture {}", name)]
+    ResourceLoad {
+        name: String,
+        #[cause]
+        inner: crate::resources::Error,
+    },
+}
+
+// Enum that represents a texture filtering mode
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    Nearest,
+    Linear,
+    NearestMipmapNearest,
+    LinearMipmapNearest,
+    NearestMipmapLinear,
+    LinearMipmapLinear,
+}
+
+// Implementation of filter
+impl Filter {
+    // Function to get the matching gl enum value
+    pub fn to_gl(self) -> gl::types::GLint {
+        (match self {
+            Filter::Nearest => gl::NEAREST,
+            Filter::Linear => gl::LINEAR,
+            Filter::NearestMipmapNearest => gl::NEAREST_MIPMAP_NEAREST,
+            Filter::LinearMipmapNearest => gl::LINEAR_MIPMAP_NEAREST,
+            Filter::NearestMipmapLinear => gl::NEAREST_MIPMAP_LINEAR,
+            Filter::LinearMipmapLinear => gl::LINEAR_MIPMAP_LINEAR,
+        }) as gl::types::GLint
+    }
+}
+
+// Enum that represents a texture wrapping mode
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Wrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    ClampToBorder,
+}
+
+// Implementation of wrap
+impl Wrap {
+    // Function to get the matching gl enum value
+    pub fn to_gl(self) -> gl::types::GLint {
+        (match self {
+            Wrap::Repeat => gl::REPEAT,
+            Wrap::MirroredRepeat => gl::MIRRORED_REPEAT,
+            Wrap::ClampToEdge => gl::CLAMP_TO_EDGE,
+            Wrap::ClampToBorder => gl::CLAMP_TO_BORDER,
+        }) as gl::types::GLint
+    }
+}
+
+// Enum that represents the format a texture stores its texels in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InternalFormat {
+    Rgba8,
+    Srgb8Alpha8,
+    R16F,
+    Rg16F,
+    Rgb16F,
+    Rgba16F,
+    R32F,
+    Rgba32F,
+    R11FG11FB10F,
+    DepthComponent16,
+    DepthComponent24,
+    DepthComponent32F,
+    Depth24Stencil8,
+}
+
+// Implementation of internal format
+impl InternalFormat {
+    // Function to get the matching gl enum value
+    pub fn to_gl(self) -> gl::types::GLenum {
+        match self {
+            InternalFormat::Rgba8 => gl::RGBA8,
+            InternalFormat::Srgb8Alpha8 => gl::SRGB8_ALPHA8,
+            InternalFormat::R16F => gl::R16F,
+            InternalFormat::Rg16F => gl::RG16F,
+            InternalFormat::Rgb16F => gl::RGB16F,
+            InternalFormat::Rgba16F => gl::RGBA16F,
+            InternalFormat::R32F => gl::R32F,
+            InternalFormat::Rgba32F => gl::RGBA32F,
+            InternalFormat::R11FG11FB10F => gl::R11F_G11F_B10F,
+            InternalFormat::DepthComponent16 => gl::DEPTH_COMPONENT16,
+            InternalFormat::DepthComponent24 => gl::DEPTH_COMPONENT24,
+            InternalFormat::DepthComponent32F => gl::DEPTH_COMPONENT32F,
+            InternalFormat::Depth24Stencil8 => gl::DEPTH24_STENCIL8,
+        }
+    }
+
+    // Function to get a pixel format and type that are valid to upload into this format
+    pub fn pixel_format_and_type(self) -> (gl::types::GLenum, gl::types::GLenum) {
+        match self {
+            InternalFormat::Rgba8 | InternalFormat::Srgb8Alpha8 => (gl::RGBA, gl::UNSIGNED_BYTE),
+            InternalFormat::R16F | InternalFormat::R32F => (gl::RED, gl::FLOAT),
+            InternalFormat::Rg16F => (gl::RG, gl::FLOAT),
+            InternalFormat::Rgb16F | InternalFormat::R11FG11FB10F => (gl::RGB, gl::FLOAT),
+            InternalFormat::Rgba16F | InternalFormat::Rgba32F => (gl::RGBA, gl::FLOAT),
+            InternalFormat::DepthComponent16
+            | InternalFormat::DepthComponent24
+            | InternalFormat::DepthComponent32F => (gl::DEPTH_COMPONENT, gl::FLOAT),
+            InternalFormat::Depth24Stencil8 => (gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8),
+        }
+    }
+
+    // Function to check if this is a depth or depth-stencil format
+    pub fn is_depth(self) -> bool {
+        matches!(
+            self,
+            InternalFormat::DepthComponent16
+                | InternalFormat::DepthComponent24
+                | InternalFormat::DepthComponent32F
+                | InternalFormat::Depth24Stencil8
+        )
+    }
+
+    // Function to check if this format has a stencil component
+    pub fn has_stencil(self) -> bool {
+        self == InternalFormat::Depth24Stencil8
+    }
+}
+
+// Struct that represents a 2D texture
+pub struct Texture2D {
+    // The gl context
+    gl: gl::Gl,
+    // The id of the texture object
+    id: gl::types::GLuint,
+    // Size of the texture in pixels
+    width: u32,
+    height: u32,
+    // Storage format of the texture
+    format: InternalFormat,
+}
+
+// Implementation of 2D texture
+impl Texture2D {
+    // Function to create an empty texture object
+    pub fn new(gl: &gl::Gl) -> Texture2D {
+        let mut id: gl::types::GLuint = 0;
+        unsafe {
+            gl.GenTextures(1, &mut id);
+        }
+
+        Texture2D {
+            gl: gl.clone(),
+            id,
+            width: 0,
+            height: 0,
+            format: InternalFormat::Rgba8,
+        }
+    }
+
+    // Function to create texture from an image resource (PNG or JPEG)
+    pub fn from_res(gl: &gl::Gl, res: &Resources, name: &str) -> Result<Texture2D, Error> {
+        // Load and decode image
+        let image = res.load_image(name).map_err(|e| Error::ResourceLoad {
+            name: name.into(),
+            inner: e,
+        })?;
+
+        // OpenGL expects the first row to be the bottom of the image
+        let mut image = image.to_rgba8();
+        image_util::flip_vertical(&mut image);
+
+        Ok(Texture2D::from_image_mipmapped(gl, &image))
+    }
+
+    // Function to create texture from a tangent space normal map resource, converting it to
+    // the OpenGL convention (green up) so shaders never need to know where it came from.
+    // With no convention given, it is guessed from the image (see
+    // image_util::detect_normal_map_convention).
+    pub fn normal_map_from_res(
+        gl: &gl::Gl,
+        res: &Resources,
+        name: &str,
+        convention: Option<NormalMapConvention>,
+    ) -> Result<Texture2D, Error> {
+        let image = res.load_image(name).map_err(|e| Error::ResourceLoad {
+            name: name.into(),
+            inner: e,
+        })?;
+
+        let mut image = image.to_rgba8();
+        image_util::flip_vertical(&mut image);
+
+        let convention =
+            convention.unwrap_or_else(|| image_util::detect_normal_map_convention(&image));
+        if convention == NormalMapConvention::DirectX {
+            image_util::flip_green(&mut image);
+        }
+
+        Ok(Texture2D::from_image_mipmapped(gl, &image))
+    }
+
+    // Function to upload pixels and build the mipmap chain, with repeating trilinear sampling
+    fn from_image_mipmapped(gl: &gl::Gl, image: &image::RgbaImage) -> Texture2D {
+        let texture = Texture2D::from_image(gl, image);
+        texture.bind();
+        texture.generate_mipmaps();
+        texture.set_filter(Filter::LinearMipmapLinear, Filter::Linear);
+        texture.set_wrap(Wrap::Repeat, Wrap::Repeat);
+        texture.unbind();
+
+        texture
+    }
+
+    // Function to create texture from a decoded image, e.g. one of glTF scene images
+    pub fn from_image(gl: &gl::Gl, image: &image::RgbaImage) -> Texture2D {
+        Texture2D::from_rgba(gl, image.width(), image.height(), image)
+    }
+
+    // Function to create texture with uninitialized storage, e.g. for render targets
+    pub fn allocate(gl: &gl::Gl, width: u32, height: u32, format: InternalFormat) -> Texture2D {
+        let mut texture = Texture2D::new(gl);
+        texture.width = width;
+        texture.height = height;
+        texture.format = format;
+
+        let (pixel_format, pixel_type) = format.pixel_format_and_type();
+
+        texture.bind();
+        unsafe {
+            gl.TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                format.to_gl() as gl::types::GLint,
+                width as gl::types::GLsizei,
+                height as gl::types::GLsizei,
+                0,
+                pixel_format,
+                pixel_type,
+                std::ptr::null(), // no data, storage only
+            );
+        }
+        // Depth textures are not filtered by default
+        if format.is_depth() {
+            texture.set_filter(Filter::Nearest, Filter::Nearest);
+        } else {
+            texture.set_filter(Filter::Linear, Filter::Linear);
+        }
+        texture.set_wrap(Wrap::ClampToEdge, Wrap::ClampToEdge);
+        texture.unbind();
+
+        texture
+    }
+
+    // Function to create texture from tightly packed RGBA8 pixels
+    pub fn from_rgba(gl: &gl::Gl, width: u32, height: u32, pixels: &[u8]) -> Texture2D {
+        assert_eq!(pixels.len(), image_util::buffer_len(width, height, 4));
+
+        let mut texture = Texture2D::new(gl);
+        texture.width = width;
+        texture.height = height;
+
+        texture.bind();
+        unsafe {
+            gl.TexImage2D(
+                gl::TEXTURE_2D,                              // target
+                0,                                           // mipmap level
+                gl::RGBA8 as gl::types::GLint,               // internal format
+                width as gl::types::GLsizei,                 // width
+                height as gl::types::GLsizei,                // height
+                0,                                           // border (must be 0)
+                gl::RGBA,                                    // format of the pixel data
+                gl::UNSIGNED_BYTE,                           // data type of the pixel data
+                pixels.as_ptr() as *const gl::types::GLvoid, // pointer to data
+            );
+        }
+        texture.set_filter(Filter::Linear, Filter::Linear);
+        texture.set_wrap(Wrap::ClampToEdge, Wrap::ClampToEdge);
+        texture.unbind();
+
+        texture
+    }
+
+    // Function to create R32F texture from tightly packed single channel floats
+    pub fn from_red_f32(gl: &gl::Gl, width: u32, height: u32, values: &[f32]) -> Texture2D {
+        assert_eq!(values.len(), image_util::buffer_len(width, height, 1));
+
+        let texture = Texture2D::allocate(gl, width, height, InternalFormat::R32F);
+        texture.bind();
+        unsafe {
+            gl.TexSubImage2D(
+                gl::TEXTURE_2D,
+                0,
+                0,
+                0,
+                width as gl::types::GLsizei,
+                height as gl::types::GLsizei,
+                gl::RED,
+                gl::FLOAT,
+                values.as_ptr() as *const gl::types::GLvoid,
+            );
+        }
+        texture.unbind();
+
+        texture
+    }
+
+    // Function to create R32F texture of noise generated on the cpu, use
+    // noise::NoiseGenerator to generate large textures on the gpu instead
+    pub fn from_noise(gl: &gl::Gl, width: u32, height: u32, params: &NoiseParams) -> Texture2D {
+        let values = noise::generate(width, height, params);
+        Texture2D::from_red_f32(gl, width, height, &values)
+    }
+
+    // Function to bind the texture
+    pub fn bind(&self) {
+        unsafe {
+            self.gl.BindTexture(gl::TEXTURE_2D, self.id);
+        }
+    }
+
+    // Function to bind the texture to the given texture unit
+    pub fn bind_at(&self, unit: u32) {
+        unsafe {
+            self.gl.ActiveTexture(gl::TEXTURE0 + unit);
+        }
+        self.bind();
+    }
+
+    // Function to unbind the texture
+    pub fn unbind(&self) {
+        unsafe {
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    // Function to set minification and magnification filters (texture must be bound)
+    pub fn set_filter(&self, min: Filter, mag: Filter) {
+        unsafe {
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, min.to_gl());
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, mag.to_gl());
+        }
+    }
+
+    // Function to set wrapping along s and t axes (texture must be bound)
+    pub fn set_wrap(&self, s: Wrap, t: Wrap) {
+        unsafe {
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, s.to_gl());
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, t.to_gl());
+        }
+    }
+
+    // Function to fill the base level with a single value, without binding the texture
+    // (color formats use all four components, depth uses the first and stencil the second),
+    // glClearTexImage is core in the 4.5 context the application requires
+    pub fn clear(&self, value: [f32; 4]) {
+        unsafe {
+            if self.format.has_stencil() {
+                let depth = (value[0].clamp(0.0, 1.0) * 0xFF_FFFF as f32) as u32;
+                let packed: u32 = depth << 8 | (value[1] as u32 & 0xFF);
+                self.gl.ClearTexImage(
+                    self.id,
+                    0,
+                    gl::DEPTH_STENCIL,
+                    gl::UNSIGNED_INT_24_8,
+                    &packed as *const u32 as *const gl::types::GLvoid,
+                );
+            } else if self.format.is_depth() {
+                self.gl.ClearTexImage(
+                    self.id,
+                    0,
+                    gl::DEPTH_COMPONENT,
+                    gl::FLOAT,
+                    value.as_ptr() as *const gl::types::GLvoid,
+                );
+            } else {
+                self.gl.ClearTexImage(
+                    self.id,
+                    0,
+                    gl::RGBA,
+                    gl::FLOAT,
+                    value.as_ptr() as *const gl::types::GLvoid,
+                );
+            }
+        }
+    }
+
+    // Function to set bias added to the computed level of detail (texture must be bound)
+    pub fn set_lod_bias(&self, bias: f32) {
+        unsafe {
+            self.gl
+                .TexParameterf(gl::TEXTURE_2D, gl::TEXTURE_LOD_BIAS, bias);
+        }
+    }
+
+    // Function to clamp the level of detail used when sampling (texture must be bound)
+    pub fn set_lod_range(&self, min: f32, max: f32) {
+        unsafe {
+            self.gl
+                .TexParameterf(gl::TEXTURE_2D, gl::TEXTURE_MIN_LOD, min);
+            self.gl
+                .TexParameterf(gl::TEXTURE_2D, gl::TEXTURE_MAX_LOD, max);
+        }
+    }
+
+    // Function to copy filtering, wrapping, level of detail, depth compare and border
+    // settings of another texture without binding either, e.g. to keep them when a texture
+    // is recreated at another size
+    pub fn copy_parameters_from(&self, other: &Texture2D) {
+        const INTEGER_PARAMETERS: [gl::types::GLenum; 6] = [
+            gl::TEXTURE_MIN_FILTER,
+            gl::TEXTURE_MAG_FILTER,
+            gl::TEXTURE_WRAP_S,
+            gl::TEXTURE_WRAP_T,
+            gl::TEXTURE_COMPARE_MODE,
+            gl::TEXTURE_COMPARE_FUNC,
+        ];
+        const FLOAT_PARAMETERS: [gl::types::GLenum; 3] = [
+            gl::TEXTURE_LOD_BIAS,
+            gl::TEXTURE_MIN_LOD,
+            gl::TEXTURE_MAX_LOD,
+        ];
+
+        unsafe {
+            for parameter in INTEGER_PARAMETERS {
+                let mut value: gl::types::GLint = 0;
+                self.gl
+                    .GetTextureParameteriv(other.id, parameter, &mut value);
+                self.gl.TextureParameteri(self.id, parameter, value);
+            }
+            for parameter in FLOAT_PARAMETERS {
+                let mut value: gl::types::GLfloat = 0.0;
+                self.gl
+                    .GetTextureParameterfv(other.id, parameter, &mut value);
+                self.gl.TextureParameterf(self.id, parameter, value);
+            }
+            let mut border = [0.0; 4];
+            self.gl
+                .GetTextureParameterfv(other.id, gl::TEXTURE_BORDER_COLOR, border.as_mut_ptr());
+            self.gl
+                .TextureParameterfv(self.id, gl::TEXTURE_BORDER_COLOR, border.as_ptr());
+        }
+    }
+
+    // Function to generate mipmaps from the base level (texture must be bound)
+    pub fn generate_mipmaps(&self) {
+        unsafe {
+            self.gl.GenerateMipmap(gl::TEXTURE_2D);
+        }
+    }
+
+    // Function to get texture id
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    // Function to get texture width
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    // Function to get texture height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // Function to get texture storage format
+    pub fn format(&self) -> InternalFormat {
+        self.format
+    }
+}
+
+// Drop trait implementation for 2D texture
+impl Drop for Texture2D {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteTextures(1, &self.id);
+        }
+    }
+}
+
+// Struct that represents a multisampled 2D texture, e.g. a multisampled render target read
+// with texelFetch(sampler2DMS, ...) or resolved into a regular texture
+pub struct Texture2DMultisample {
+    // The gl context
+    gl: gl::Gl,
+    // The id of the texture object
+    id: gl::types::GLuint,
+    // Size of the texture in pixels
+    width: u32,
+    height: u32,
+    // Storage format of the texture
+    format: InternalFormat,
+    // Number of samples per pixel
+    samples: u32,
+}
+
+// Implementation of multisampled 2D texture
+impl Texture2DMultisample {
+    // Function to create texture with uninitialized storage, all pixels use the same sample
+    // locations so it can be attached together with multisampled renderbuffers
+    pub fn allocate(
+        gl: &gl::Gl,
+        width: u32,
+        height: u32,
+        format: InternalFormat,
+        samples: u32,
+    ) -> Texture2DMultisample {
+        let mut id: gl::types::GLuint = 0;
+        unsafe {
+            gl.GenTextures(1, &mut id);
+            gl.BindTexture(gl::TEXTURE_2D_MULTISAMPLE, id);
+            gl.TexImage2DMultisample(
+                gl::TEXTURE_2D_MULTISAMPLE,
+                samples as gl::types::GLsizei,
+                format.to_gl(),
+                width as gl::types::GLsizei,
+                height as gl::types::GLsizei,
+                gl::TRUE, // fixed sample locations
+            );
+            gl.BindTexture(gl::TEXTURE_2D_MULTISAMPLE, 0);
+        }
+
+        Texture2DMultisample {
+            gl: gl.clone(),
+            id,
+            width,
+            height,
+            format,
+            samples,
+        }
+    }
+
+    // Function to bind the texture to the given texture unit
+    pub fn bind_at(&self, unit: u32) {
+        unsafe {
+            self.gl.ActiveTexture(gl::TEXTURE0 + unit);
+            self.gl.BindTexture(gl::TEXTURE_2D_MULTISAMPLE, self.id);
+        }
+    }
+
+    // Function to get texture id
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    // Function to get texture width
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    // Function to get texture height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // Function to get texture storage format
+    pub fn format(&self) -> InternalFormat {
+        self.format
+    }
+
+    // Function to get number of samples per pixel
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+}
+
+// Drop trait implementation for multisampled 2D texture
+impl Drop for Texture2DMultisample {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteTextures(1, &self.id);
+        }
+    }
+}
+
+// Struct that represents a sampler object, which overrides sampling state of the texture
+// bound to the same texture unit
+pub struct Sampler {
+    gl: gl::Gl,
+    id: gl::types::GLuint,
+}
+
+// Implementation of sampler
+impl Sampler {
+    // Function to create sampler with default gl sampling state
+    pub fn new(gl: &gl::Gl) -> Sampler {
+        let mut id: gl::types::GLuint = 0;
+        unsafe {
+            gl.GenSamplers(1, &mut id);
+        }
+
+        Sampler { gl: gl.clone(), id }
+    }
+
+    // Function to bind the sampler to the given texture unit
+    pub fn bind_at(&self, unit: u32) {
+        unsafe {
+            self.gl.BindSampler(unit, self.id);
+        }
+    }
+
+    // Function to unbind any sampler from the given texture unit
+    pub fn unbind_at(&self, unit: u32) {
+        unsafe {
+            self.gl.BindSampler(unit, 0);
+        }
+    }
+
+    // Function to set minification and magnification filters
+    pub fn set_filter(&self, min: Filter, mag: Filter) {
+        unsafe {
+            self.gl
+                .SamplerParameteri(self.id, gl::TEXTURE_MIN_FILTER, min.to_gl());
+            self.gl
+                .SamplerParameteri(self.id, gl::TEXTURE_MAG_FILTER, mag.to_gl());
+        }
+    }
+
+    // Function to set wrapping along s, t and r axes
+    pub fn set_wrap(&self, s: Wrap, t: Wrap, r: Wrap) {
+        unsafe {
+            self.gl
+                .SamplerParameteri(self.id, gl::TEXTURE_WRAP_S, s.to_gl());
+            self.gl
+                .SamplerParameteri(self.id, gl::TEXTURE_WRAP_T, t.to_gl());
+            self.gl
+                .SamplerParameteri(self.id, gl::TEXTURE_WRAP_R, r.to_gl());
+        }
+    }
+
+    // Function to set bias added to the computed level of detail
+    pub fn set_lod_bias(&self, bias: f32) {
+        unsafe {
+            self.gl
+                .SamplerParameterf(self.id, gl::TEXTURE_LOD_BIAS, bias);
+        }
+    }
+
+    // Function to clamp the level of detail, e.g. to pick a roughness level of a
+    // prefiltered environment map
+    pub fn set_lod_range(&self, min: f32, max: f32) {
+        unsafe {
+            self.gl.SamplerParameterf(self.id, gl::TEXTURE_MIN_LOD, min);
+            self.gl.SamplerParameterf(self.id, gl::TEXTURE_MAX_LOD, max);
+        }
+    }
+
+    // Function to get sampler id
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+// Drop trait implementation for sampler
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteSamplers(1, &self.id);
+        }
+    }
+}
+
+// Function to enable filtering across cube map faces, required for correct sampling of
+// prefiltered environment maps (global state, enabled for all cube maps)
+pub fn enable_seamless_cubemap(gl: &gl::Gl) {
+    unsafe {
+        gl.Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+    }
+}
@@ -0,0 +1,160 @@
+// Import dependencies
+use gl;
+
+use crate::render::data::AttributeDesc;
+
+// Trait to represent a vertex struct whose attribute layout is known, implemented by
+// #[derive(VertexAttribPointers)] so buffers and meshes can set up attributes on their own
+pub trait Vertex {
+    // Function to get description of every attribute of the vertex
+    fn layout() -> &'static [AttributeDesc];
+
+    // Function to enable and set attribute pointers of the vertex (vertex array and array
+    // buffer must be bound)
+    fn setup_attribs(gl: &gl::Gl);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::data;
+
+    // Vertex with an explicit location followed by automatic ones, padding and raw integers
+    #[derive(VertexAttribPointers, Copy, Clone)]
+    #[repr(C, packed)]
+    struct Named {
+        #[location = 2]
+        pos: data::VertVec3D,
+        color: data::VertRGBA,
+        #[skip]
+        _padding: [u8; 4],
+        #[normalized]
+        weights: [u16; 2],
+        #[integer]
+        ids: [u8; 4],
+        #[location = "8"]
+        uv: data::VertVec2D,
+    }
+
+    // Tuple vertex with half float and normalized byte data types
+    #[derive(VertexAttribPointers, Copy, Clone)]
+    #[repr(C, packed)]
+    struct Tuple(
+        data::VertVec2F16,
+        #[location = 5] data::VertI8Float,
+        #[skip] u8,
+        data::VertF16,
+    );
+
+    // Instance data with a matrix at location 1, its columns take locations 1 to 4
+    #[derive(VertexAttribPointers, Copy, Clone)]
+    #[repr(C, packed)]
+    struct Instance {
+        #[location = 1]
+        #[divisor = 1]
+        model: data::VertMat4,
+        tint: data::VertVec4D,
+    }
+
+    // Function to create description of an attribute
+    fn desc(
+        location: u32,
+        components: i32,
+        gl_type: gl::types::GLenum,
+        normalized: bool,
+        integer: bool,
+        offset: usize,
+    ) -> AttributeDesc {
+        AttributeDesc {
+            location,
+            components,
+            gl_type,
+            normalized,
+            integer,
+            offset,
+        }
+    }
+
+    #[test]
+    fn named_struct_layout() {
+        assert_eq!(
+            Named::layout(),
+            &[
+                desc(2, 3, gl::FLOAT, false, false, 0),
+                desc(3, 4, gl::UNSIGNED_INT_2_10_10_10_REV, true, false, 12),
+                desc(4, 2, gl::UNSIGNED_SHORT, true, false, 20),
+                desc(5, 4, gl::UNSIGNED_BYTE, false, true, 24),
+                desc(8, 2, gl::FLOAT, false, false, 28),
+            ]
+        );
+    }
+
+    #[test]
+    fn tuple_struct_layout() {
+        assert_eq!(
+            Tuple::layout(),
+            &[
+                desc(0, 2, gl::HALF_FLOAT, false, false, 0),
+                desc(5, 1, gl::BYTE, true, false, 4),
+                desc(6, 1, gl::HALF_FLOAT, false, false, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn matrix_takes_one_location_per_column() {
+        assert_eq!(
+            Instance::layout(),
+            &[
+                desc(1, 4, gl::FLOAT, false, false, 0),
+                desc(2, 4, gl::FLOAT, false, false, 16),
+                desc(3, 4, gl::FLOAT, false, false, 32),
+                desc(4, 4, gl::FLOAT, false, false, 48),
+                desc(5, 4, gl::FLOAT, false, false, 64),
+            ]
+        );
+    }
+
+    // The validating context of gl_debug checks for a bound array buffer
+    #[cfg(not(feature = "gl_debug"))]
+    #[test]
+    fn divisor_applies_to_every_matrix_column() {
+        use std::cell::RefCell;
+
+        thread_local! {
+            // Locations and divisors passed to glVertexAttribDivisor
+            static DIVISORS: RefCell<Vec<(u32, u32)>> = const { RefCell::new(Vec::new()) };
+        }
+
+        extern "system" fn enable_vertex_attrib_array(_index: gl::types::GLuint) {}
+
+        extern "system" fn vertex_attrib_pointer(
+            _index: gl::types::GLuint,
+            _size: gl::types::GLint,
+            _type: gl::types::GLenum,
+            _normalized: gl::types::GLboolean,
+            _stride: gl::types::GLsizei,
+            _pointer: *const gl::types::GLvoid,
+        ) {
+        }
+
+        extern "system" fn vertex_attrib_divisor(
+            index: gl::types::GLuint,
+            divisor: gl::types::GLuint,
+        ) {
+            DIVISORS.with(|divisors| divisors.borrow_mut().push((index, divisor)));
+        }
+
+        // Context with just the functions setting up attributes, others are not loaded
+        let gl = gl::Gl::load_with(|name| match name {
+            "glEnableVertexAttribArray" => enable_vertex_attrib_array as *const _,
+            "glVertexAttribPointer" => vertex_attrib_pointer as *const _,
+            "glVertexAttribDivisor" => vertex_attrib_divisor as *const _,
+            _ => ::std::ptr::null(),
+        });
+
+        Instance::setup_attribs(&gl);
+        let divisors = DIVISORS.with(|divisors| divisors.borrow().clone());
+        assert_eq!(divisors, [(1, 1), (2, 1), (3, 1), (4, 1)]);
+    }
+}
@@ -0,0 +1,3 @@
+// Assets compiled into the executable by the build script, as (resource name, contents)
+// pairs. The list is only filled when the `embed_assets` feature is enabled.
+include!(concat!(env!("OUT_DIR"), "/embedded_assets.rs"));
@@ -0,0 +1,186 @@
+// Import dependencies
+use std::collections::HashMap;
+
+use crate::math::Vec3;
+
+// Largest number of vertices and triangles in a meshlet, small enough for one work group
+// to process a meshlet and for triangles to index vertices with a byte
+pub const MAX_VERTICES: usize = 64;
+pub const MAX_TRIANGLES: usize = 64;
+
+// Struct that represents a cluster of neighbouring triangles with bounds to cull it as a
+// whole, e.g. on the gpu before drawing (see render::meshlet)
+#[derive(Clone, Debug)]
+pub struct Meshlet {
+    // Indices into the vertices of the mesh
+    pub vertices: Vec<u32>,
+    // Triangles as indices into the meshlet vertices
+    pub triangles: Vec<[u8; 3]>,
+    // Bounding sphere
+    pub center: [f32; 3],
+    pub radius: f32,
+    // Cone containing all triangle normals, the meshlet faces away from a camera at p when
+    // dot(center - p, cone_axis) >= cone_cutoff * |center - p| + radius
+    pub cone_axis: [f32; 3],
+    // Sine of the cone half angle, 1 when the normals spread too far to ever cull
+    pub cone_cutoff: f32,
+}
+
+// Function to split indexed triangles into meshlets, triangles are taken in index order so
+// meshes with good vertex locality (e.g. optimized for the vertex cache) give tight bounds
+pub fn build(positions: &[[f32; 3]], indices: &[u32]) -> Vec<Meshlet> {
+    let mut meshlets = Vec::new();
+    let mut vertices: Vec<u32> = Vec::new();
+    let mut triangles: Vec<[u8; 3]> = Vec::new();
+    // Meshlet vertex of each mesh vertex in the current meshlet
+    let mut local: HashMap<u32, u8> = HashMap::new();
+
+    for triangle in indices.chunks_exact(3) {
+        let new_vertices = triangle
+            .iter()
+            .enumerate()
+            .filter(|&(i, index)| !local.contains_key(index) && !triangle[..i].contains(index))
+            .count();
+        if vertices.len() + new_vertices > MAX_VERTICES || triangles.len() == MAX_TRIANGLES {
+            meshlets.push(finish(positions, &vertices, &triangles));
+            vertices.clear();
+            triangles.clear();
+            local.clear();
+        }
+
+        let mut corners = [0u8; 3];
+        for (corner, &index) in corners.iter_mut().zip(triangle) {
+            *corner = *local.entry(index).or_insert_with(|| {
+                vertices.push(index);
+                (vertices.len() - 1) as u8
+            });
+        }
+        triangles.push(corners);
+    }
+    if !triangles.is_empty() {
+        meshlets.push(finish(positions, &vertices, &triangles));
+    }
+
+    meshlets
+}
+
+// Helper function to create a meshlet and compute its bounds
+fn finish(positions: &[[f32; 3]], vertices: &[u32], triangles: &[[u8; 3]]) -> Meshlet {
+    let position = |local: u8| Vec3::from(positions[vertices[local as usize] as usize]);
+
+    // Sphere around the center of the bounding box
+    let (min, max) = vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), &index| {
+            let p = Vec3::from(positions[index as usize]);
+            (min.min(p), max.max(p))
+        },
+    );
+    let center = (min + max) * 0.5;
+    let radius = vertices
+        .iter()
+        .map(|&index| Vec3::from(positions[index as usize]).distance(center))
+        .fold(0.0, f32::max);
+
+    // Cone around the average normal, degenerate triangles have no normal
+    let normals: Vec<Vec3> = triangles
+        .iter()
+        .filter_map(|&[a, b, c]| {
+            let (a, b, c) = (position(a), position(b), position(c));
+            (b - a).cross(c - a).try_normalize()
+        })
+        .collect();
+    let axis = normals.iter().copied().sum::<Vec3>().normalize_or_zero();
+    let min_dot = normals
+        .iter()
+        .map(|normal| normal.dot(axis))
+        .fold(1.0, f32::min);
+    let cone_cutoff = if axis == Vec3::ZERO || min_dot <= 0.0 {
+        1.0
+    } else {
+        (1.0 - min_dot * min_dot).sqrt()
+    };
+
+    Meshlet {
+        vertices: vertices.to_vec(),
+        triangles: triangles.to_vec(),
+        center: center.to_array(),
+        radius,
+        cone_axis: axis.to_array(),
+        cone_cutoff,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function to build a flat grid facing +z with two triangles per cell
+    fn grid(size: u32) -> (Vec<[f32; 3]>, Vec<u32>) {
+        let positions = (0..=size)
+            .flat_map(|y| (0..=size).map(move |x| [x as f32, y as f32, 0.0]))
+            .collect();
+        let mut indices = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                let i = y * (size + 1) + x;
+                indices.extend_from_slice(&[i, i + 1, i + size + 2, i, i + size + 2, i + size + 1]);
+            }
+        }
+        (positions, indices)
+    }
+
+    #[test]
+    fn every_triangle_appears_once() {
+        let (positions, indices) = grid(20);
+        let meshlets = build(&positions, &indices);
+
+        let mut built: Vec<[u32; 3]> = meshlets
+            .iter()
+            .flat_map(|meshlet| {
+                meshlet
+                    .triangles
+                    .iter()
+                    .map(move |t| t.map(|i| meshlet.vertices[i as usize]))
+            })
+            .collect();
+        let mut expected: Vec<[u32; 3]> = indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+        built.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(built, expected);
+    }
+
+    #[test]
+    fn limits_are_respected() {
+        let (positions, indices) = grid(20);
+        let meshlets = build(&positions, &indices);
+
+        assert!(meshlets.len() > 1);
+        for meshlet in &meshlets {
+            assert!(!meshlet.triangles.is_empty());
+            assert!(meshlet.vertices.len() <= MAX_VERTICES);
+            assert!(meshlet.triangles.len() <= MAX_TRIANGLES);
+            assert!(meshlet
+                .triangles
+                .iter()
+                .flatten()
+                .all(|&i| (i as usize) < meshlet.vertices.len()));
+        }
+    }
+
+    #[test]
+    fn flat_meshlet_has_tight_cone() {
+        let (positions, indices) = grid(2);
+        let meshlets = build(&positions, &indices);
+
+        assert_eq!(meshlets.len(), 1);
+        let meshlet = &meshlets[0];
+        assert_eq!(meshlet.cone_axis, [0.0, 0.0, 1.0]);
+        assert!(meshlet.cone_cutoff.abs() < 1e-3);
+        assert!((meshlet.center[0] - 1.0).abs() < 1e-5 && (meshlet.center[1] - 1.0).abs() < 1e-5);
+        assert!(meshlet.radius >= 2.0f32.sqrt() - 1e-5);
+    }
+}
@@ -0,0 +1,336 @@
+// Import dependencies
+use notify::Watcher;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc;
+
+// Import embedded module from src/resources/embedded.rs
+pub mod embedded;
+// Import meshlet module from src/resources/meshlet.rs
+pub mod meshlet;
+// Import model module from src/resources/model.rs
+pub mod model;
+// Import scene module from src/resources/scene.rs
+pub mod scene;
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(display = "I/O error")]
+    Io(#[cause] io::Error),
+    #[fail(display = "Failed to read CString from file that contains 0")]
+    FileContainsNil,
+    #[fail(display = "Failed get executable path")]
+    FailedToGetExePath,
+    #[fail(display = "Failed to decode image")]
+    Image(#[cause] image::ImageError),
+    #[fail(display = "Failed to watch resources directory")]
+    Watch(#[cause] notify::Error),
+    #[fail(display = "Resource {} is not embedded into the executable", _0)]
+    NotEmbedded(String),
+    #[fail(display = "Failed to read resource archive")]
+    Archive(#[cause] zip::result::ZipError),
+}
+
+// Enum that represents where resources are loaded from
+enum Backend {
+    // Files in a directory on disk
+    Directory(PathBuf),
+    // Files compiled into the executable, as (resource name, contents) pairs
+    Embedded(&'static [(&'static str, &'static [u8])]),
+    // Files packed into a zip archive
+    Archive(RefCell<zip::ZipArchive<fs::File>>),
+}
+
+// Struct that holds filesystem watcher and the channel it sends events to
+struct ResourceWatcher {
+    // Watcher stops when dropped, so it is kept alive here
+    _watcher: notify::RecommendedWatcher,
+    events: mpsc::Receiver<notify::Result<notify::Event>>,
+}
+
+// Struct that holds statistics of the resource cache
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    // Loads served from the cache
+    pub hits: u64,
+    // Loads which had to read the file
+    pub misses: u64,
+    // Number of cached resources
+    pub entries: usize,
+    // Total size of cached resources in bytes
+    pub bytes: usize,
+}
+
+// Resources struct
+pub struct Resources {
+    backend: Backend,
+    // Filesystem watcher, present after watch() is called
+    watcher: Option<ResourceWatcher>,
+    // Number of changes seen for each watched file
+    versions: RefCell<HashMap<PathBuf, u64>>,
+    // Contents of loaded resources keyed by resource name
+    cache: RefCell<HashMap<String, Rc<[u8]>>>,
+    // Cache statistics (entries and bytes are computed on request)
+    stats: RefCell<CacheStats>,
+}
+
+// Implementation of Resources struct
+impl Resources {
+    pub fn from_relative_exe_path(rel_path: &Path) -> Result<Resources, Error> {
+        // Get path to executable
+        let exe_file_name = ::std::env::current_exe().map_err(|_| Error::FailedToGetExePath)?;
+        // Get path to directory containing executable
+        let exe_path = exe_file_name.parent().ok_or(Error::FailedToGetExePath)?;
+        // Get path to resources directory
+        let res_path = exe_path.join(rel_path);
+
+        Ok(Resources::from_path(&res_path))
+    }
+
+    // Create resources rooted at the given directory
+    pub fn from_path(root_path: &Path) -> Resources {
+        Resources::from_backend(Backend::Directory(root_path.into()))
+    }
+
+    // Create resources served from files compiled into the executable, e.g.
+    // `Resources::from_embedded(resources::embedded::ASSETS)`
+    pub fn from_embedded(files: &'static [(&'static str, &'static [u8])]) -> Resources {
+        Resources::from_backend(Backend::Embedded(files))
+    }
+
+    // Create resources served from a zip archive (e.g. assets.pak), resource names are
+    // paths inside the archive
+    pub fn from_archive(archive_path: &Path) -> Result<Resources, Error> {
+        let archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+
+        let backend = Backend::Archive(RefCell::new(archive));
+
+        Ok(Resources::from_backend(backend))
+    }
+
+    fn from_backend(backend: Backend) -> Resources {
+        Resources {
+            backend,
+            watcher: None,
+            versions: RefCell::new(HashMap::new()),
+            cache: RefCell::new(HashMap::new()),
+            stats: RefCell::new(CacheStats::default()),
+        }
+    }
+
+    // Start watching the resources directory for changes, see resource_version
+    // (embedded and archived resources are not watched)
+    pub fn watch(&mut self) -> Result<(), Error> {
+        let root_path = match self.backend {
+            Backend::Directory(ref root_path) => root_path,
+            Backend::Embedded(_) | Backend::Archive(_) => return Ok(()),
+        };
+
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(root_path, notify::RecursiveMode::Recursive)?;
+
+        self.watcher = Some(ResourceWatcher {
+            _watcher: watcher,
+            events,
+        });
+
+        Ok(())
+    }
+
+    // Get number of changes of a resource seen since watch() was called, comparing it with
+    // a previously stored version tells if the resource needs to be reloaded
+    pub fn resource_version(&self, resource_name: &str) -> u64 {
+        self.apply_watch_events();
+
+        let root_path = match self.backend {
+            Backend::Directory(ref root_path) => root_path,
+            Backend::Embedded(_) | Backend::Archive(_) => return 0,
+        };
+
+        self.versions
+            .borrow()
+            .get(&resource_name_to_path(root_path, resource_name))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    // Apply pending filesystem events: bump versions and drop changed files from the cache
+    fn apply_watch_events(&self) {
+        let (watcher, root_path) = match (&self.watcher, &self.backend) {
+            (Some(watcher), Backend::Directory(root_path)) => (watcher, root_path),
+            _ => return,
+        };
+
+        let mut versions = self.versions.borrow_mut();
+        let mut cache = self.cache.borrow_mut();
+        for event in watcher.events.try_iter().flatten() {
+            if event.kind.is_access() {
+                continue;
+            }
+            for path in event.paths {
+                cache.retain(|name, _| resource_name_to_path(root_path, name) != path);
+                *versions.entry(path).or_insert(0) += 1;
+            }
+        }
+    }
+
+    // Load a resource through the cache, the file is only read on first load or after the
+    // resource was invalidated
+    fn load_cached(&self, resource_name: &str) -> Result<Rc<[u8]>, Error> {
+        self.apply_watch_events();
+
+        if let Some(data) = self.cache.borrow().get(resource_name) {
+            self.stats.borrow_mut().hits += 1;
+            return Ok(data.clone());
+        }
+
+        let data: Rc<[u8]> = self.read(resource_name)?.into();
+        self.cache
+            .borrow_mut()
+            .insert(resource_name.into(), data.clone());
+        self.stats.borrow_mut().misses += 1;
+
+        Ok(data)
+    }
+
+    // Read a resource from the backend, bypassing the cache
+    fn read(&self, resource_name: &str) -> Result<Vec<u8>, Error> {
+        match self.backend {
+            Backend::Directory(ref root_path) => {
+                // Open file
+                let mut file = fs::File::open(resource_name_to_path(root_path, resource_name))?;
+
+                // allocate buffer of the same size as file
+                let mut buffer: Vec<u8> = Vec::with_capacity(file.metadata()?.len() as usize + 1);
+                file.read_to_end(&mut buffer)?;
+
+                Ok(buffer)
+            }
+            Backend::Embedded(files) => files
+                .iter()
+                .find(|&&(name, _)| name == resource_name)
+                .map(|&(_, data)| data.to_vec())
+                .ok_or_else(|| Error::NotEmbedded(resource_name.into())),
+            Backend::Archive(ref archive) => {
+                let mut archive = archive.borrow_mut();
+                let mut file = archive.by_name(resource_name)?;
+
+                let mut buffer: Vec<u8> = Vec::with_capacity(file.size() as usize + 1);
+                file.read_to_end(&mut buffer)?;
+
+                Ok(buffer)
+            }
+        }
+    }
+
+    // Check if a resource exists, without loading it
+    pub fn exists(&self, resource_name: &str) -> bool {
+        if self.cache.borrow().contains_key(resource_name) {
+            return true;
+        }
+
+        match self.backend {
+            Backend::Directory(ref root_path) => {
+                resource_name_to_path(root_path, resource_name).is_file()
+            }
+            Backend::Embedded(files) => files.iter().any(|&(name, _)| name == resource_name),
+            Backend::Archive(ref archive) => archive
+                .borrow()
+                .file_names()
+                .any(|name| name == resource_name),
+        }
+    }
+
+    // Load a resource into a byte buffer
+    pub fn load_cstring(&self, resource_name: &str) -> Result<ffi::CString, Error> {
+        let data = self.load_cached(resource_name)?;
+
+        // check for nul byte
+        if data.contains(&0) {
+            return Err(Error::FileContainsNil);
+        }
+
+        Ok(unsafe { ffi::CString::from_vec_unchecked(data.to_vec()) })
+    }
+
+    // Load a resource as raw bytes
+    pub fn load_bytes(&self, resource_name: &str) -> Result<Vec<u8>, Error> {
+        Ok(self.load_cached(resource_name)?.to_vec())
+    }
+
+    // Drop a resource from the cache, so the next load reads it again
+    pub fn invalidate(&self, resource_name: &str) {
+        self.cache.borrow_mut().remove(resource_name);
+    }
+
+    // Drop all resources from the cache
+    pub fn clear_cache(&self) {
+        self.cache.borrow_mut().clear();
+    }
+
+    // Get statistics of the resource cache
+    pub fn cache_stats(&self) -> CacheStats {
+        let cache = self.cache.borrow();
+        CacheStats {
+            entries: cache.len(),
+            bytes: cache.values().map(|data| data.len()).sum(),
+            ..*self.stats.borrow()
+        }
+    }
+
+    // Load and decode an image resource (format is guessed from the file contents)
+    pub fn load_image(&self, resource_name: &str) -> Result<image::DynamicImage, Error> {
+        let bytes = self.load_bytes(resource_name)?;
+        Ok(image::load_from_memory(&bytes)?)
+    }
+}
+
+// Implement From trait for Error enum
+impl From<io::Error> for Error {
+    // Convert io::Error to Error
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+// Implement From trait for Error enum
+impl From<image::ImageError> for Error {
+    // Convert image::ImageError to Error
+    fn from(error: image::ImageError) -> Self {
+        Error::Image(error)
+    }
+}
+
+// Implement From trait for Error enum
+impl From<notify::Error> for Error {
+    // Convert notify::Error to Error
+    fn from(error: notify::Error) -> Self {
+        Error::Watch(error)
+    }
+}
+
+// Implement From trait for Error enum
+impl From<zip::result::ZipError> for Error {
+    // Convert zip::result::ZipError to Error
+    fn from(error: zip::result::ZipError) -> Self {
+        Error::Archive(error)
+    }
+}
+
+// Convert resource name to path
+fn resource_name_to_path(root_dir: &Path, location: &str) -> PathBuf {
+    let mut path: PathBuf = root_dir.into();
+
+    for part in location.split("/") {
+        path = path.join(part);
+    }
+
+    path
+}
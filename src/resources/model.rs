@@ -0,0 +1,147 @@
+// Import dependencies
+use std::io::Cursor;
+
+use crate::resources::meshlet::{self, Meshlet};
+use crate::resources::Resources;
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(display = "Failed to load resource {}", name)]
+    ResourceLoad {
+        name: String,
+        #[cause]
+        inner: crate::resources::Error,
+    },
+    #[fail(display = "Failed to parse model {}", name)]
+    Parse {
+        name: String,
+        #[cause]
+        inner: tobj::LoadError,
+    },
+    #[fail(display = "Failed to parse materials of model {}", name)]
+    MaterialParse {
+        name: String,
+        #[cause]
+        inner: tobj::LoadError,
+    },
+}
+
+// Struct that represents a material referenced by a model
+#[derive(Clone, Debug)]
+pub struct ModelMaterial {
+    pub name: String,
+    pub diffuse: [f32; 3],
+    // Resource name of the diffuse texture, relative to the resources root
+    pub diffuse_texture: Option<String>,
+}
+
+// Struct that represents a triangulated part of a model using a single material
+#[derive(Clone, Debug)]
+pub struct ModelMesh {
+    pub name: String,
+    pub positions: Vec<[f32; 3]>,
+    // Empty if the model has no normals
+    pub normals: Vec<[f32; 3]>,
+    // Empty if the model has no texture coordinates
+    pub tex_coords: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+    // Index into model materials
+    pub material_id: Option<usize>,
+}
+
+// Implementation of model mesh
+impl ModelMesh {
+    // Function to split the mesh into meshlets of up to 64 triangles for cluster culling
+    pub fn meshlets(&self) -> Vec<Meshlet> {
+        meshlet::build(&self.positions, &self.indices)
+    }
+}
+
+// Struct that represents model loaded from a Wavefront OBJ file
+#[derive(Clone, Debug)]
+pub struct Model {
+    pub meshes: Vec<ModelMesh>,
+    pub materials: Vec<ModelMaterial>,
+}
+
+// Implementation of model
+impl Model {
+    // Function to load model from .obj resource, materials are loaded from .mtl
+    // resources next to it
+    pub fn from_res(res: &Resources, name: &str) -> Result<Model, Error> {
+        // Load obj source
+        let bytes = res.load_bytes(name).map_err(|e| Error::ResourceLoad {
+            name: name.into(),
+            inner: e,
+        })?;
+
+        // Directory of the obj resource, material paths are relative to it
+        let dir = match name.rfind('/') {
+            Some(i) => &name[..i + 1],
+            None => "",
+        };
+
+        // Parse obj, splitting it into triangulated meshes per material group
+        let load_options = tobj::LoadOptions {
+            single_index: true,
+            triangulate: true,
+            ..Default::default()
+        };
+        let (models, materials) =
+            tobj::load_obj_buf(&mut Cursor::new(bytes), &load_options, |mtl_path| {
+                let mtl_name = format!("{}{}", dir, mtl_path.to_string_lossy());
+                let mtl_bytes = res
+                    .load_bytes(&mtl_name)
+                    .map_err(|_| tobj::LoadError::OpenFileFailed)?;
+                tobj::load_mtl_buf(&mut Cursor::new(mtl_bytes))
+            })
+            .map_err(|e| Error::Parse {
+                name: name.into(),
+                inner: e,
+            })?;
+        let materials = materials.map_err(|e| Error::MaterialParse {
+            name: name.into(),
+            inner: e,
+        })?;
+
+        Ok(Model {
+            meshes: models
+                .into_iter()
+                .map(|model| ModelMesh {
+                    name: model.name,
+                    positions: model
+                        .mesh
+                        .positions
+                        .chunks_exact(3)
+                        .map(|p| [p[0], p[1], p[2]])
+                        .collect(),
+                    normals: model
+                        .mesh
+                        .normals
+                        .chunks_exact(3)
+                        .map(|n| [n[0], n[1], n[2]])
+                        .collect(),
+                    tex_coords: model
+                        .mesh
+                        .texcoords
+                        .chunks_exact(2)
+                        .map(|t| [t[0], t[1]])
+                        .collect(),
+                    indices: model.mesh.indices,
+                    material_id: model.mesh.material_id,
+                })
+                .collect(),
+            materials: materials
+                .into_iter()
+                .map(|material| ModelMaterial {
+                    name: material.name,
+                    diffuse: material.diffuse.unwrap_or([1.0, 1.0, 1.0]),
+                    diffuse_texture: material
+                        .diffuse_texture
+                        .map(|texture| format!("{}{}", dir, texture)),
+                })
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,378 @@
+// Import dependencies
+use base64::Engine;
+
+use crate::math::Mat4;
+use crate::resources::model::ModelMesh;
+use crate::resources::Resources;
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(display = "Failed to load resource {}", name)]
+    ResourceLoad {
+        name: String,
+        #[cause]
+        inner: crate::resources::Error,
+    },
+    #[fail(display = "Failed to parse glTF scene {}", name)]
+    Parse {
+        name: String,
+        #[cause]
+        inner: gltf::Error,
+    },
+    #[fail(display = "Failed to decode data URI in glTF scene {}", name)]
+    DataUri { name: String },
+    #[fail(display = "Binary glTF scene {} has no BIN chunk", name)]
+    MissingBlob { name: String },
+    #[fail(
+        display = "Buffer {} of glTF scene {} has {} bytes, expected {}",
+        index, name, length, expected
+    )]
+    TruncatedBuffer {
+        name: String,
+        index: usize,
+        length: usize,
+        expected: usize,
+    },
+    #[fail(
+        display = "Buffer view {} of glTF scene {} is outside of its buffer",
+        index, name
+    )]
+    BufferViewRange { name: String, index: usize },
+    #[fail(
+        display = "Invalid percent-encoding in URI {} of glTF scene {}",
+        uri, name
+    )]
+    InvalidUri { name: String, uri: String },
+    #[fail(display = "Failed to decode image {} of glTF scene {}", index, name)]
+    Image {
+        name: String,
+        index: usize,
+        #[cause]
+        inner: image::ImageError,
+    },
+}
+
+// Struct that represents a metallic-roughness material of a scene
+#[derive(Clone, Debug)]
+pub struct SceneMaterial {
+    pub name: String,
+    pub base_color_factor: [f32; 4],
+    // Index into scene images
+    pub base_color_texture: Option<usize>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+}
+
+// Struct that represents a mesh of a scene, made of parts with a single material each
+#[derive(Clone, Debug)]
+pub struct SceneMesh {
+    pub name: String,
+    pub primitives: Vec<ModelMesh>,
+}
+
+// Struct that represents a node in the scene hierarchy
+#[derive(Clone, Debug)]
+pub struct SceneNode {
+    pub name: String,
+    // Transform relative to the parent node
+    pub transform: Mat4,
+    // Index into scene meshes
+    pub mesh: Option<usize>,
+    // Indices into scene nodes
+    pub children: Vec<usize>,
+}
+
+// Struct that represents a scene loaded from a glTF 2.0 (.gltf or .glb) file
+pub struct Scene {
+    pub meshes: Vec<SceneMesh>,
+    pub materials: Vec<SceneMaterial>,
+    // Decoded images, rows are stored top to bottom as glTF texture coordinates expect
+    pub images: Vec<image::RgbaImage>,
+    pub nodes: Vec<SceneNode>,
+    // Indices of the root nodes of the default scene
+    pub roots: Vec<usize>,
+}
+
+// Implementation of scene
+impl Scene {
+    // Function to load scene from .gltf or .glb resource, external buffers and images are
+    // loaded from resources next to it
+    pub fn from_res(res: &Resources, name: &str) -> Result<Scene, Error> {
+        // Load glTF source
+        let bytes = load_resource(res, name)?;
+        let gltf = gltf::Gltf::from_slice(&bytes).map_err(|e| Error::Parse {
+            name: name.into(),
+            inner: e,
+        })?;
+
+        // Directory of the glTF resource, uris are relative to it
+        let dir = match name.rfind('/') {
+            Some(i) => &name[..i + 1],
+            None => "",
+        };
+
+        // Resolve buffers
+        let mut buffers = Vec::new();
+        for buffer in gltf.buffers() {
+            let data = match buffer.source() {
+                gltf::buffer::Source::Bin => gltf
+                    .blob
+                    .clone()
+                    .ok_or_else(|| Error::MissingBlob { name: name.into() })?,
+                gltf::buffer::Source::Uri(uri) => load_uri(res, name, dir, uri)?,
+            };
+            // Readers index into buffers by the declared length
+            if data.len() < buffer.length() {
+                return Err(Error::TruncatedBuffer {
+                    name: name.into(),
+                    index: buffer.index(),
+                    length: data.len(),
+                    expected: buffer.length(),
+                });
+            }
+            buffers.push(data);
+        }
+
+        // Decode images
+        let mut images = Vec::new();
+        for image in gltf.images() {
+            let data = match image.source() {
+                gltf::image::Source::View { view, .. } => {
+                    let range = view.offset()..view.offset().saturating_add(view.length());
+                    buffers
+                        .get(view.buffer().index())
+                        .and_then(|buffer| buffer.get(range))
+                        .ok_or_else(|| Error::BufferViewRange {
+                            name: name.into(),
+                            index: view.index(),
+                        })?
+                        .to_vec()
+                }
+                gltf::image::Source::Uri { uri, .. } => load_uri(res, name, dir, uri)?,
+            };
+            let decoded = image::load_from_memory(&data).map_err(|e| Error::Image {
+                name: name.into(),
+                index: image.index(),
+                inner: e,
+            })?;
+            images.push(decoded.to_rgba8());
+        }
+
+        // Read materials
+        let materials = gltf
+            .materials()
+            .map(|material| {
+                let pbr = material.pbr_metallic_roughness();
+                SceneMaterial {
+                    name: material.name().unwrap_or_default().into(),
+                    base_color_factor: pbr.base_color_factor(),
+                    base_color_texture: pbr
+                        .base_color_texture()
+                        .map(|info| info.texture().source().index()),
+                    metallic_factor: pbr.metallic_factor(),
+                    roughness_factor: pbr.roughness_factor(),
+                }
+            })
+            .collect();
+
+        // Read mesh primitives, lines and points are skipped as meshes only draw triangles
+        let meshes = gltf
+            .meshes()
+            .map(|mesh| SceneMesh {
+                name: mesh.name().unwrap_or_default().into(),
+                primitives: mesh
+                    .primitives()
+                    .filter_map(|primitive| {
+                        let reader =
+                            primitive.reader(|buffer| buffers.get(buffer.index()).map(|b| &b[..]));
+                        let positions: Vec<[f32; 3]> = reader
+                            .read_positions()
+                            .map(|positions| positions.collect())
+                            .unwrap_or_default();
+                        let indices = match reader.read_indices() {
+                            Some(indices) => indices.into_u32().collect(),
+                            None => (0..positions.len() as u32).collect(),
+                        };
+                        let indices = triangle_list(primitive.mode(), indices)?;
+
+                        Some(ModelMesh {
+                            name: mesh.name().unwrap_or_default().into(),
+                            normals: reader
+                                .read_normals()
+                                .map(|normals| normals.collect())
+                                .unwrap_or_default(),
+                            tex_coords: reader
+                                .read_tex_coords(0)
+                                .map(|tex_coords| tex_coords.into_f32().collect())
+                                .unwrap_or_default(),
+                            positions,
+                            indices,
+                            material_id: primitive.material().index(),
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        // Read node hierarchy
+        let nodes = gltf
+            .nodes()
+            .map(|node| SceneNode {
+                name: node.name().unwrap_or_default().into(),
+                transform: Mat4::from_cols_array_2d(&node.transform().matrix()),
+                mesh: node.mesh().map(|mesh| mesh.index()),
+                children: node.children().map(|child| child.index()).collect(),
+            })
+            .collect();
+
+        // Use default scene, or the first one if none is marked as default
+        let roots = gltf
+            .default_scene()
+            .or_else(|| gltf.scenes().next())
+            .map(|scene| scene.nodes().map(|node| node.index()).collect())
+            .unwrap_or_default();
+
+        Ok(Scene {
+            meshes,
+            materials,
+            images,
+            nodes,
+            roots,
+        })
+    }
+
+    // Function to compute world transform of every node by walking down from the roots
+    pub fn world_transforms(&self) -> Vec<Mat4> {
+        let mut world = vec![Mat4::IDENTITY; self.nodes.len()];
+        let mut stack: Vec<(usize, Mat4)> = self
+            .roots
+            .iter()
+            .map(|&root| (root, Mat4::IDENTITY))
+            .collect();
+
+        while let Some((index, parent)) = stack.pop() {
+            let node = &self.nodes[index];
+            world[index] = parent * node.transform;
+            for &child in &node.children {
+                stack.push((child, world[index]));
+            }
+        }
+
+        world
+    }
+}
+
+// Helper function to convert indices of a primitive to a triangle list, keeping the winding
+// of every triangle as the glTF spec defines it, None for lines and points
+fn triangle_list(mode: gltf::mesh::Mode, indices: Vec<u32>) -> Option<Vec<u32>> {
+    let triangles = indices.len().saturating_sub(2);
+    match mode {
+        gltf::mesh::Mode::Triangles => Some(indices),
+        gltf::mesh::Mode::TriangleStrip => Some(
+            (0..triangles)
+                .flat_map(|i| {
+                    let odd = i % 2;
+                    [indices[i], indices[i + 1 + odd], indices[i + 2 - odd]]
+                })
+                .collect(),
+        ),
+        gltf::mesh::Mode::TriangleFan => Some(
+            (0..triangles)
+                .flat_map(|i| [indices[i + 1], indices[i + 2], indices[0]])
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+// Helper function to load bytes of a resource
+fn load_resource(res: &Resources, name: &str) -> Result<Vec<u8>, Error> {
+    res.load_bytes(name).map_err(|e| Error::ResourceLoad {
+        name: name.into(),
+        inner: e,
+    })
+}
+
+// Helper function to load data referenced by uri, either embedded as base64 data uri or
+// stored in a resource relative to the scene
+fn load_uri(res: &Resources, name: &str, dir: &str, uri: &str) -> Result<Vec<u8>, Error> {
+    if uri.starts_with("data:") {
+        let encoded = match uri.split_once(";base64,") {
+            Some((_, encoded)) => encoded,
+            None => return Err(Error::DataUri { name: name.into() }),
+        };
+        return base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|_| Error::DataUri { name: name.into() });
+    }
+
+    // Relative references are URI encoded, e.g. spaces are written as %20
+    let path = percent_decode(uri).ok_or_else(|| Error::InvalidUri {
+        name: name.into(),
+        uri: uri.into(),
+    })?;
+    load_resource(res, &format!("{}{}", dir, path))
+}
+
+// Helper function to decode %XX escapes of an URI, None if an escape is malformed or the
+// result is not UTF-8
+fn percent_decode(uri: &str) -> Option<String> {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3)?;
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            let hex = std::str::from_utf8(hex).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gltf::mesh::Mode;
+
+    #[test]
+    fn triangles_are_kept() {
+        let indices = vec![0, 1, 2, 2, 1, 3];
+        assert_eq!(
+            triangle_list(Mode::Triangles, indices.clone()),
+            Some(indices)
+        );
+    }
+
+    #[test]
+    fn strip_alternates_winding() {
+        assert_eq!(
+            triangle_list(Mode::TriangleStrip, vec![0, 1, 2, 3, 4]),
+            Some(vec![0, 1, 2, 1, 3, 2, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn fan_shares_first_vertex() {
+        assert_eq!(
+            triangle_list(Mode::TriangleFan, vec![0, 1, 2, 3]),
+            Some(vec![1, 2, 0, 2, 3, 0])
+        );
+    }
+
+    #[test]
+    fn lines_and_points_are_skipped() {
+        assert_eq!(triangle_list(Mode::Lines, vec![0, 1]), None);
+        assert_eq!(triangle_list(Mode::Points, vec![0]), None);
+        assert_eq!(triangle_list(Mode::TriangleStrip, vec![0, 1]), Some(vec![]));
+    }
+}
@@ -0,0 +1,132 @@
+// Import dependencies
+use std::time::Instant;
+
+// Name of the uniform block time is shared through, shaders declare it as
+// `layout (std140) uniform Time { float Total; float Delta; float Unscaled; uint Frame; };`
+pub const TIME_BLOCK: &str = "Time";
+
+// Struct that represents time data laid out to match the std140 Time block
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct TimeBlock {
+    pub total: f32,
+    pub delta: f32,
+    pub unscaled: f32,
+    pub frame: u32,
+}
+
+// Struct that tracks frame time, advanced once per frame with tick()
+#[derive(Clone, Debug)]
+pub struct Time {
+    // Moment of the previous tick
+    last_tick: Instant,
+    // Scaled time since start in seconds, stops while paused
+    total: f64,
+    // Real time since start in seconds
+    unscaled_total: f64,
+    // Scaled duration of the last frame in seconds, zero while paused
+    delta: f32,
+    // Real duration of the last frame in seconds
+    unscaled_delta: f32,
+    // Number of ticks since start
+    frame: u64,
+    // Multiplier applied to delta, e.g. 0.25 for slow motion
+    scale: f32,
+    paused: bool,
+}
+
+// Implementation of time
+impl Time {
+    // Constructor for time starting now
+    pub fn new() -> Time {
+        Time {
+            last_tick: Instant::now(),
+            total: 0.0,
+            unscaled_total: 0.0,
+            delta: 0.0,
+            unscaled_delta: 0.0,
+            frame: 0,
+            scale: 1.0,
+            paused: false,
+        }
+    }
+
+    // Function to advance time by the real time passed since the previous tick
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_tick).as_secs_f32();
+        self.last_tick = now;
+
+        self.unscaled_delta = elapsed;
+        self.unscaled_total += elapsed as f64;
+        self.delta = if self.paused {
+            0.0
+        } else {
+            elapsed * self.scale
+        };
+        self.total += self.delta as f64;
+        self.frame += 1;
+    }
+
+    // Function to get scaled time since start in seconds
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    // Function to get real time since start in seconds
+    pub fn unscaled_total(&self) -> f64 {
+        self.unscaled_total
+    }
+
+    // Function to get scaled duration of the last frame in seconds
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    // Function to get real duration of the last frame in seconds
+    pub fn unscaled_delta(&self) -> f32 {
+        self.unscaled_delta
+    }
+
+    // Function to get the number of frames since start
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    // Function to get the time scale
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    // Function to set the time scale, negative values are clamped to zero
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.max(0.0);
+    }
+
+    // Function to check if time is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Function to pause or resume scaled time, real time keeps running
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    // Function to get data for the Time uniform block
+    pub fn block(&self) -> TimeBlock {
+        TimeBlock {
+            total: self.total as f32,
+            delta: self.delta,
+            unscaled: self.unscaled_total as f32,
+            frame: self.frame as u32,
+        }
+    }
+}
+
+// Implement default trait for time
+impl Default for Time {
+    fn default() -> Self {
+        Self::new()
+    }
+}
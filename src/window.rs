@@ -0,0 +1,156 @@
+// Import dependencies
+use sdl2::mouse::{Cursor, SystemCursor};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+use sdl2::video::Window;
+use sdl2::VideoSubsystem;
+use std::time::{Duration, Instant};
+
+use crate::resources::Resources;
+
+// Enum which holds all the error's that can occur
+#[derive(Debug, Fail)] // Dervice Fail, in addition to Debug which is derived by default
+pub enum Error {
+    #[fail(display = "Failed to load image {}", name)]
+    ResourceLoad {
+        name: String,
+        #[cause]
+        inner: crate::resources::Error,
+    },
+    #[fail(display = "SDL error: {}", message)]
+    Sdl { message: String },
+    #[fail(display = "Window title contains 0")]
+    TitleContainsNil,
+}
+
+// Implement From trait for Error enum
+impl From<String> for Error {
+    // Convert SDL error message to Error
+    fn from(message: String) -> Self {
+        Error::Sdl { message }
+    }
+}
+
+// Function to set window icon from an image resource (e.g. 32x32 or 64x64 png)
+pub fn set_icon(window: &mut Window, res: &Resources, name: &str) -> Result<(), Error> {
+    let mut image = load_rgba(res, name)?;
+    let (width, height) = image.dimensions();
+
+    let surface = Surface::from_data(
+        &mut image,
+        width,
+        height,
+        width * 4,
+        PixelFormatEnum::RGBA32,
+    )?;
+    window.set_icon(surface);
+
+    Ok(())
+}
+
+// Function to set window title
+pub fn set_title(window: &mut Window, title: &str) -> Result<(), Error> {
+    window.set_title(title).map_err(|_| Error::TitleContainsNil)
+}
+
+// Function to create one of the cursors provided by the system, the cursor is shown by
+// calling set() on it and has to be kept alive while in use
+pub fn system_cursor(cursor: SystemCursor) -> Result<Cursor, Error> {
+    Ok(Cursor::from_system(cursor)?)
+}
+
+// Function to create a cursor from an image resource, hot_x and hot_y give the clicking
+// point in pixels from the top left corner. The cursor is shown by calling set() on it
+// and has to be kept alive while in use.
+pub fn cursor_from_res(
+    res: &Resources,
+    name: &str,
+    hot_x: i32,
+    hot_y: i32,
+) -> Result<Cursor, Error> {
+    let mut image = load_rgba(res, name)?;
+    let (width, height) = image.dimensions();
+
+    let surface = Surface::from_data(
+        &mut image,
+        width,
+        height,
+        width * 4,
+        PixelFormatEnum::RGBA32,
+    )?;
+    Ok(Cursor::from_surface(surface, hot_x, hot_y)?)
+}
+
+// Function to request a multisampled default framebuffer for windows created afterwards,
+// 0 samples disables multisampling
+pub fn set_gl_multisample(video: &VideoSubsystem, samples: u8) {
+    let gl_attr = video.gl_attr();
+    gl_attr.set_multisample_buffers(if samples > 0 { 1 } else { 0 });
+    gl_attr.set_multisample_samples(samples);
+}
+
+// Function to get text from the clipboard, empty if it holds no text
+pub fn clipboard_text(video: &VideoSubsystem) -> Result<String, Error> {
+    let clipboard = video.clipboard();
+    if !clipboard.has_clipboard_text() {
+        return Ok(String::new());
+    }
+
+    Ok(clipboard.clipboard_text()?)
+}
+
+// Function to put text into the clipboard
+pub fn set_clipboard_text(video: &VideoSubsystem, text: &str) -> Result<(), Error> {
+    Ok(video.clipboard().set_clipboard_text(text)?)
+}
+
+// Struct that appends frames per second to the window title, refreshed once per interval
+pub struct TitleFps {
+    // Title the frame rate is appended to
+    base_title: String,
+    // How often the title is refreshed
+    interval: Duration,
+    // Start of the current interval
+    interval_start: Instant,
+    // Frames drawn in the current interval
+    frames: u32,
+}
+
+// Implementation of title fps
+impl TitleFps {
+    // Constructor for a title refreshed once per second
+    pub fn new(base_title: &str) -> TitleFps {
+        TitleFps {
+            base_title: base_title.into(),
+            interval: Duration::from_secs(1),
+            interval_start: Instant::now(),
+            frames: 0,
+        }
+    }
+
+    // Function to count a frame, call once per frame
+    pub fn frame(&mut self, window: &mut Window) -> Result<(), Error> {
+        self.frames += 1;
+
+        let elapsed = self.interval_start.elapsed();
+        if elapsed < self.interval {
+            return Ok(());
+        }
+
+        let fps = self.frames as f64 / elapsed.as_secs_f64();
+        self.frames = 0;
+        self.interval_start = Instant::now();
+
+        set_title(window, &format!("{} - {:.0} FPS", self.base_title, fps))
+    }
+}
+
+// Helper function to load image resource as rgba pixels
+fn load_rgba(res: &Resources, name: &str) -> Result<image::RgbaImage, Error> {
+    res.load_image(name)
+        .map(|image| image.to_rgba8())
+        .map_err(|e| Error::ResourceLoad {
+            name: name.into(),
+            inner: e,
+        })
+}